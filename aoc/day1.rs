@@ -1,28 +1,30 @@
 // https://adventofcode.com/2022/day/1
-use aoc2022::{Part, Result};
-use std::io::{self, BufRead, BufReader, Read};
+use aoc2022::{Error, Format, Mode, Options, Part, Result};
+use itertools::Itertools;
+use std::{
+    cmp::Reverse,
+    collections::BinaryHeap,
+    fmt,
+    io::{self, BufRead, BufReader, Read},
+};
 
 #[derive(Default, Debug, PartialEq, Eq)]
 struct Elf {
     meals: Vec<u32>,
-    total_calories: u32,
+    total_calories: u64,
 }
 
 impl Elf {
-    fn new() -> Self {
-        Elf::default()
-    }
-
     fn add_meal(&mut self, calories: u32) {
         self.meals.push(calories);
-        self.total_calories += calories;
+        self.total_calories += u64::from(calories);
     }
 
     #[cfg(test)]
     fn with_meals(meals: &[u32]) -> Self {
         let mut total = 0;
         for meal in meals {
-            total += meal;
+            total += u64::from(*meal);
         }
 
         Elf {
@@ -37,72 +39,415 @@ fn split_bytes<R: Read>(r: R) -> impl Iterator<Item = io::Result<Vec<u8>>> {
     buf.split(b'\n')
 }
 
-fn parse_elves<R: Read>(reader: R) -> Result<Vec<Elf>> {
-    let initial: (usize, Vec<Elf>) = (0, Vec::new());
-    let iter = split_bytes(reader);
-    iter.map(|line| {
-        let bytes = line?;
-        let line = String::from_utf8(bytes)?;
-        Ok(line)
-    })
-    .try_fold(initial, |(n, mut elves), line: Result<String>| {
-        let line = line?;
-        if line.is_empty() {
-            // Acts as a "flush", putting a new elf on the stack.
-            elves.push(Elf::new());
-            return Ok((n, elves));
+/// Anything that can be built up one meal at a time while reading an inventory.
+///
+/// Implemented for `Elf`, which remembers every meal, and for `u64`, which keeps only the running total.
+///
+/// Totals are kept wider than meals so that an elf carrying a lot of large meals doesn't overflow.
+trait Inventory: Default {
+    fn add_meal(&mut self, calories: u32);
+}
+
+impl Inventory for Elf {
+    fn add_meal(&mut self, calories: u32) {
+        Elf::add_meal(self, calories)
+    }
+}
+
+impl Inventory for u64 {
+    fn add_meal(&mut self, calories: u32) {
+        *self += u64::from(calories);
+    }
+}
+
+/// How to treat blank lines that don't neatly separate two elves, such as leading, trailing or repeated blank lines, and lines containing only whitespace.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+enum Blanks {
+    /// Reject them, reporting the line they were found on.
+    Strict,
+    /// Collapse them, so that they never produce an elf with no meals.
+    Lenient,
+}
+
+impl Blanks {
+    fn from_options(options: &Options) -> Self {
+        if options.strict {
+            Blanks::Strict
+        } else {
+            Blanks::Lenient
+        }
+    }
+}
+
+/// Lazily reads inventories from a reader, yielding each elf as soon as the blank line after it is seen.
+///
+/// Only the elf currently being read is held in memory, so this can be run over arbitrarily large inputs.
+struct Elves<L, I> {
+    lines: L,
+    blanks: Blanks,
+    current: Option<I>,
+    /// The number of the last line read, counting from 1.
+    line: usize,
+    /// The line number of the blank line that ended the previous elf, if nothing has been read since.
+    separator: Option<usize>,
+}
+
+fn elves<R: Read, I: Inventory>(
+    reader: R,
+    blanks: Blanks,
+) -> Elves<impl Iterator<Item = io::Result<Vec<u8>>>, I> {
+    Elves {
+        lines: split_bytes(reader),
+        blanks,
+        current: None,
+        line: 0,
+        separator: None,
+    }
+}
+
+impl<L, I> Elves<L, I>
+where
+    L: Iterator<Item = io::Result<Vec<u8>>>,
+    I: Inventory,
+{
+    fn error(&self, line: usize, message: &str) -> Error {
+        Error::Parse {
+            line,
+            message: message.into(),
+        }
+    }
+
+    /// Consumes a single line, returning an elf if the line finished one off.
+    fn read_line(&mut self, bytes: io::Result<Vec<u8>>) -> Result<Option<I>> {
+        self.line += 1;
+        let line = String::from_utf8(bytes?)?;
+        let blank = line.trim().is_empty();
+        if blank && !line.is_empty() && self.blanks == Blanks::Strict {
+            return Err(self.error(self.line, "line contains only whitespace"));
         }
 
-        let calories = str::parse::<u32>(line.as_str())?;
-        let elf = {
-            if elves.is_empty() {
-                // This is our first non-empty line.
-                elves.push(Elf::new());
+        if blank {
+            // Acts as a "flush", yielding the elf we were reading.
+            return match (self.current.take(), self.blanks) {
+                (Some(elf), _) => {
+                    self.separator = Some(self.line);
+                    Ok(Some(elf))
+                }
+                (None, Blanks::Strict) => {
+                    Err(self.error(self.line, "blank line without an elf before it"))
+                }
+                (None, Blanks::Lenient) => Ok(None),
             };
+        }
 
-            // It's not possible for this to be None because we already check if its empty above.
-            elves.last_mut().unwrap()
+        let text = match self.blanks {
+            Blanks::Strict => line.as_str(),
+            Blanks::Lenient => line.trim(),
         };
 
-        elf.add_meal(calories);
-        Ok((n, elves))
-    })
-    .map(|(_, elves)| elves)
+        let calories =
+            str::parse::<u32>(text).map_err(|err| self.error(self.line, &format!("{err}")))?;
+        self.separator = None;
+        self.current
+            .get_or_insert_with(I::default)
+            .add_meal(calories);
+        Ok(None)
+    }
+}
+
+impl<L, I> Iterator for Elves<L, I>
+where
+    L: Iterator<Item = io::Result<Vec<u8>>>,
+    I: Inventory,
+{
+    type Item = Result<I>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(bytes) = self.lines.next() {
+            match self.read_line(bytes) {
+                Ok(None) => continue,
+                result => return result.transpose(),
+            }
+        }
+
+        if let (Some(line), Blanks::Strict) = (self.separator.take(), self.blanks) {
+            return Some(Err(self.error(line, "blank line without an elf after it")));
+        }
+
+        self.current.take().map(Ok)
+    }
+}
+
+fn parse_elves<R: Read>(reader: R, blanks: Blanks) -> Result<Vec<Elf>> {
+    elves(reader, blanks).collect()
+}
+
+/// The highest-carrying elves in an inventory.
+#[derive(Debug, PartialEq, Eq)]
+struct TopElves {
+    /// (index, total calories) of each elf, highest total first. Elves with equal totals are in the order they were listed.
+    elves: Vec<(usize, u64)>,
+    /// How many elves were left out despite carrying exactly as much as the last elf that made the cut.
+    ties: usize,
+    /// How many elves there were in total.
+    count: usize,
 }
 
-fn find_maximum_calories<R: Read>(reader: R) -> Result<Option<u32>> {
-    let elves = parse_elves(reader)?;
-    let totals = elves.iter().map(|elf| elf.total_calories);
-    Ok(totals.max())
+impl TopElves {
+    fn total(&self) -> u64 {
+        self.elves.iter().map(|(_, total)| total).sum()
+    }
+
+    fn cutoff(&self) -> Option<u64> {
+        self.elves.last().map(|(_, total)| *total)
+    }
 }
 
-fn find_top_three_highest_calories<R: Read>(mut reader: R) -> Result<Option<u32>> {
-    let mut elves = parse_elves(&mut reader)?;
-    elves.sort_unstable_by_key(|elf| elf.total_calories);
-    elves.reverse();
+/// Keeps the largest `n` totals seen, using a min-heap so that memory stays bounded by `n` rather than by the input.
+///
+/// When totals are tied at the cutoff, the elf listed first is kept so that the result doesn't depend on heap order.
+fn top_n<I: Iterator<Item = Result<u64>>>(totals: I, n: usize) -> Result<TopElves> {
+    let mut heap = BinaryHeap::with_capacity(n + 1);
+    let mut count = 0;
+    // A total below everything in the heap is evicted as soon as it's pushed, so evictions aren't in order; only the largest evicted total can tie with the cutoff, so that's the one to track.
+    let mut evicted: Option<(u64, usize)> = None;
+    for (index, total) in totals.enumerate() {
+        count += 1;
+        heap.push(Reverse((total?, Reverse(index))));
+        if heap.len() > n {
+            let Some(Reverse((total, _))) = heap.pop() else {
+                continue;
+            };
+
+            evicted = match evicted {
+                Some((last, ties)) if last == total => Some((last, ties + 1)),
+                Some((last, ties)) if last > total => Some((last, ties)),
+                _ => Some((total, 1)),
+            };
+        }
+    }
 
-    let total = match &elves[..=2] {
-        [a, b, c] => Some(a.total_calories + b.total_calories + c.total_calories),
-        _ => None,
+    let elves: Vec<(usize, u64)> = heap
+        .into_sorted_vec()
+        .into_iter()
+        .map(|Reverse((total, Reverse(index)))| (index, total))
+        .collect();
+
+    let ties = match (evicted, elves.last()) {
+        (Some((total, ties)), Some((_, cutoff))) if total == *cutoff => ties,
+        _ => 0,
     };
 
-    Ok(total)
+    Ok(TopElves { elves, ties, count })
+}
+
+/// Finds the `n` elves carrying the most calories, failing if there aren't that many elves to begin with.
+fn find_top_calories<R: Read>(reader: R, n: usize, blanks: Blanks) -> Result<TopElves> {
+    if n == 0 {
+        return Err(Error::InvalidInput("--top must be at least 1".into()));
+    }
+
+    let top = top_n(elves(reader, blanks), n)?;
+    if top.count < n {
+        return Err(Error::InvalidInput(format!(
+            "asked for the top {n} elves, but the inventory only lists {}",
+            top.count
+        )));
+    }
+
+    Ok(top)
+}
+
+/// Summary statistics over the inventories of every elf, for when the answer to the puzzle isn't enough.
+#[derive(Debug, PartialEq)]
+struct Stats {
+    elves: usize,
+    /// (min, mean, max) meals carried by a single elf.
+    meals: (usize, f64, usize),
+    mean: f64,
+    median: f64,
+    /// (percentile, total) pairs, using the nearest-rank method.
+    percentiles: Vec<(u32, u64)>,
+    /// (lowest total, highest total, number of elves) for each bucket of the histogram.
+    histogram: Vec<(u64, u64, usize)>,
+    /// Indices of the highest-carrying elves, highest first.
+    top: Vec<usize>,
+}
+
+const PERCENTILES: [u32; 5] = [10, 25, 75, 90, 99];
+const HISTOGRAM_BUCKETS: u64 = 10;
+const HISTOGRAM_WIDTH: usize = 40;
+
+impl Stats {
+    fn new(elves: &[Elf], top: usize) -> Option<Self> {
+        if elves.is_empty() {
+            return None;
+        }
+
+        let count = elves.len();
+        let mut totals: Vec<u64> = elves.iter().map(|elf| elf.total_calories).collect();
+        totals.sort_unstable();
+
+        let meals = elves.iter().map(|elf| elf.meals.len());
+        let meals = (
+            meals.clone().min().unwrap_or(0),
+            meals.clone().sum::<usize>() as f64 / count as f64,
+            meals.max().unwrap_or(0),
+        );
+
+        let mean = totals.iter().map(|total| *total as f64).sum::<f64>() / count as f64;
+        let median = if count.is_multiple_of(2) {
+            (totals[count / 2 - 1] as f64 + totals[count / 2] as f64) / 2.0
+        } else {
+            totals[count / 2] as f64
+        };
+
+        let percentiles = PERCENTILES
+            .iter()
+            .map(|p| {
+                let rank = (*p as usize * count).div_ceil(100).max(1);
+                (*p, totals[rank - 1])
+            })
+            .collect();
+
+        let (min, max) = (totals[0], totals[count - 1]);
+        let width = ((max - min) / HISTOGRAM_BUCKETS + 1).max(1);
+        let mut histogram: Vec<(u64, u64, usize)> = (0..HISTOGRAM_BUCKETS)
+            .map(|n| min + n * width)
+            .take_while(|start| *start <= max)
+            .map(|start| (start, start + width - 1, 0))
+            .collect();
+
+        for total in &totals {
+            histogram[((total - min) / width) as usize].2 += 1;
+        }
+
+        let mut ranked: Vec<(usize, u64)> = elves
+            .iter()
+            .map(|elf| elf.total_calories)
+            .enumerate()
+            .collect();
+        ranked.sort_by_key(|(index, total)| (Reverse(*total), *index));
+
+        Some(Stats {
+            elves: count,
+            meals,
+            mean,
+            median,
+            percentiles,
+            histogram,
+            top: ranked
+                .into_iter()
+                .take(top)
+                .map(|(index, _)| index)
+                .collect(),
+        })
+    }
+
+    fn to_json(&self) -> String {
+        let percentiles = self
+            .percentiles
+            .iter()
+            .map(|(p, total)| format!("\"p{p}\":{total}"))
+            .join(",");
+
+        let histogram = self
+            .histogram
+            .iter()
+            .map(|(low, high, count)| {
+                format!("{{\"low\":{low},\"high\":{high},\"elves\":{count}}}")
+            })
+            .join(",");
+
+        format!(
+            "{{\"elves\":{},\"meals\":{{\"min\":{},\"mean\":{},\"max\":{}}},\"mean\":{},\"median\":{},\"percentiles\":{{{percentiles}}},\"histogram\":[{histogram}],\"top\":[{}]}}",
+            self.elves,
+            self.meals.0,
+            self.meals.1,
+            self.meals.2,
+            self.mean,
+            self.median,
+            self.top.iter().join(",")
+        )
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:<16}{}", "elves", self.elves)?;
+        writeln!(
+            f,
+            "{:<16}{} / {:.2} / {}",
+            "meals per elf", self.meals.0, self.meals.1, self.meals.2
+        )?;
+        writeln!(f, "{:<16}{:.2}", "mean", self.mean)?;
+        writeln!(f, "{:<16}{:.1}", "median", self.median)?;
+        for (p, total) in &self.percentiles {
+            writeln!(f, "{:<16}{total}", format!("p{p}"))?;
+        }
+
+        writeln!(f, "{:<16}{}", "top elves", self.top.iter().join(", "))?;
+        writeln!(f)?;
+
+        let tallest = self
+            .histogram
+            .iter()
+            .map(|(_, _, count)| *count)
+            .max()
+            .unwrap_or(1);
+        for (low, high, count) in &self.histogram {
+            let bar = "#".repeat((count * HISTOGRAM_WIDTH).div_ceil(tallest));
+            writeln!(f, "{low:>8} - {high:<8} {count:>6} {bar}")?;
+        }
+
+        Ok(())
+    }
 }
 
-pub(crate) fn execute<R: Read>(part: &Part, mut reader: R) -> Result<()> {
-    let calories = match part {
-        Part::Part1 => find_maximum_calories(&mut reader)?.unwrap_or(0),
-        Part::Part2 => find_top_three_highest_calories(&mut reader)?.unwrap_or(0),
+fn report_stats<R: Read>(reader: R, options: &Options) -> Result<()> {
+    let elves = parse_elves(reader, Blanks::from_options(options))?;
+    let stats = Stats::new(&elves, options.top.unwrap_or(3))
+        .ok_or_else(|| Error::InvalidInput("the inventory lists no elves".into()))?;
+
+    match options.format {
+        Format::Table => print!("{stats}"),
+        Format::Json => println!("{}", stats.to_json()),
     };
 
-    println!("{calories}");
+    Ok(())
+}
+
+pub(crate) fn execute<R: Read>(part: &Part, options: &Options, reader: R) -> Result<()> {
+    match options.mode {
+        Some(Mode::Stats) => report_stats(reader, options),
+        Some(mode) => Err(Error::UnsupportedMode(mode)),
+        None => solve(part, options, reader),
+    }
+}
+
+fn solve<R: Read>(part: &Part, options: &Options, reader: R) -> Result<()> {
+    let n = options.top.unwrap_or(match part {
+        Part::Part1 => 1,
+        Part::Part2 => 3,
+    });
+
+    let top = find_top_calories(reader, n, Blanks::from_options(options))?;
+    if top.ties > 0 {
+        eprintln!(
+            "{} elf(s) left out despite also carrying {} calories; the first listed were kept",
+            top.ties,
+            top.cutoff().unwrap_or(0)
+        );
+    }
+
+    println!("{}", top.total());
 
     Ok(())
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{find_maximum_calories, find_top_three_highest_calories, parse_elves, Elf};
+    use super::{elves, find_top_calories, parse_elves, top_n, Blanks, Elf, Stats};
     const TEST_DOCUMENT: &[u8] = b"
 1000
 2000
@@ -122,7 +467,7 @@ mod tests {
 
     #[test]
     fn it_populates_elves_correctly() {
-        let elves = &parse_elves(TEST_DOCUMENT).unwrap()[..];
+        let elves = &parse_elves(TEST_DOCUMENT, Blanks::Lenient).unwrap()[..];
         let expected = &[
             Elf::with_meals(&[1000, 2000, 3000]),
             Elf::with_meals(&[4000]),
@@ -140,14 +485,171 @@ mod tests {
 
     #[test]
     fn it_calculates_max_calories_of_elves() {
-        assert_eq!(Some(24000), find_maximum_calories(TEST_DOCUMENT).unwrap());
+        assert_eq!(
+            24000,
+            find_top_calories(TEST_DOCUMENT, 1, Blanks::Lenient)
+                .unwrap()
+                .total()
+        );
     }
 
     #[test]
     fn it_calculates_calories_of_top_3_elves() {
         assert_eq!(
-            Some(45000),
-            find_top_three_highest_calories(TEST_DOCUMENT).unwrap()
+            45000,
+            find_top_calories(TEST_DOCUMENT, 3, Blanks::Lenient)
+                .unwrap()
+                .total()
+        );
+    }
+
+    #[test]
+    fn it_streams_totals_without_keeping_meals() {
+        let totals: Vec<u64> = elves(TEST_DOCUMENT, Blanks::Lenient)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(vec![6000, 4000, 11000, 24000, 10000], totals);
+    }
+
+    #[test]
+    fn it_keeps_only_the_top_n_totals() {
+        let totals = [5, 1, 9, 3, 7].into_iter().map(Ok);
+        let top = top_n(totals, 2).unwrap();
+        assert_eq!(vec![(2, 9), (4, 7)], top.elves);
+        assert_eq!(0, top.ties);
+        assert_eq!(5, top.count);
+    }
+
+    #[test]
+    fn it_calculates_any_number_of_top_elves() {
+        assert_eq!(
+            55000,
+            find_top_calories(TEST_DOCUMENT, 5, Blanks::Lenient)
+                .unwrap()
+                .total()
+        );
+    }
+
+    #[test]
+    fn it_errors_when_there_are_too_few_elves() {
+        assert!(find_top_calories(TEST_DOCUMENT, 6, Blanks::Lenient).is_err());
+        assert!(find_top_calories(&b"100\n"[..], 3, Blanks::Lenient).is_err());
+        assert!(find_top_calories(&b""[..], 1, Blanks::Lenient).is_err());
+    }
+
+    #[test]
+    fn it_breaks_ties_at_the_cutoff_by_order() {
+        let totals = [7, 5, 9, 5, 5].into_iter().map(Ok);
+        let top = top_n(totals, 3).unwrap();
+        assert_eq!(vec![(2, 9), (0, 7), (1, 5)], top.elves);
+        assert_eq!(2, top.ties);
+        assert_eq!(Some(5), top.cutoff());
+    }
+
+    #[test]
+    fn it_adds_up_totals_beyond_u32() {
+        let document = &b"4000000000\n300000000\n"[..];
+        let top = find_top_calories(document, 1, Blanks::Lenient).unwrap();
+        assert_eq!(4_300_000_000, top.total());
+        let elves = parse_elves(document, Blanks::Lenient).unwrap();
+        assert_eq!(4_300_000_000, elves[0].total_calories);
+    }
+
+    #[test]
+    fn it_counts_ties_when_smaller_totals_follow() {
+        let totals = [5, 5, 5, 1].into_iter().map(Ok);
+        let top = top_n(totals, 2).unwrap();
+        assert_eq!(vec![(0, 5), (1, 5)], top.elves);
+        assert_eq!(1, top.ties);
+    }
+
+    #[test]
+    fn it_summarises_inventories() {
+        let elves = parse_elves(TEST_DOCUMENT, Blanks::Lenient).unwrap();
+        let stats = Stats::new(&elves, 3).unwrap();
+        assert_eq!(5, stats.elves);
+        assert_eq!((1, 2.0, 3), stats.meals);
+        assert_eq!(11000.0, stats.mean);
+        assert_eq!(10000.0, stats.median);
+        assert_eq!(
+            vec![
+                (10, 4000),
+                (25, 6000),
+                (75, 11000),
+                (90, 24000),
+                (99, 24000)
+            ],
+            stats.percentiles
+        );
+        assert_eq!(vec![3, 2, 4], stats.top);
+        assert_eq!(
+            5,
+            stats
+                .histogram
+                .iter()
+                .map(|(_, _, count)| count)
+                .sum::<usize>()
+        );
+        assert_eq!((4000, 6000, 2), stats.histogram[0]);
+        assert_eq!(
+            r#"{"elves":5,"meals":{"min":1,"mean":2,"max":3},"mean":11000,"median":10000,"percentiles":{"p10":4000,"p25":6000,"p75":11000,"p90":24000,"p99":24000},"histogram":[{"low":4000,"high":6000,"elves":2},{"low":6001,"high":8001,"elves":0},{"low":8002,"high":10002,"elves":1},{"low":10003,"high":12003,"elves":1},{"low":12004,"high":14004,"elves":0},{"low":14005,"high":16005,"elves":0},{"low":16006,"high":18006,"elves":0},{"low":18007,"high":20007,"elves":0},{"low":20008,"high":22008,"elves":0},{"low":22009,"high":24009,"elves":1}],"top":[3,2,4]}"#,
+            stats.to_json()
+        );
+    }
+
+    #[test]
+    fn it_has_no_stats_without_elves() {
+        assert_eq!(None, Stats::new(&[], 3));
+    }
+
+    #[test]
+    fn both_modes_agree_on_well_formed_input() {
+        let well_formed = &TEST_DOCUMENT[1..];
+        assert_eq!(
+            parse_elves(well_formed, Blanks::Strict).unwrap(),
+            parse_elves(well_formed, Blanks::Lenient).unwrap()
+        );
+        assert_eq!(
+            parse_elves(TEST_DOCUMENT, Blanks::Lenient).unwrap(),
+            parse_elves(well_formed, Blanks::Strict).unwrap()
+        );
+    }
+
+    #[test]
+    fn lenient_mode_collapses_stray_blank_lines() {
+        let elves = parse_elves(&b"\n\n100\n \n\n200\n300\n\n\n"[..], Blanks::Lenient).unwrap();
+        assert_eq!(
+            vec![Elf::with_meals(&[100]), Elf::with_meals(&[200, 300])],
+            elves
+        );
+    }
+
+    #[test]
+    fn strict_mode_reports_where_stray_blank_lines_are() {
+        let error = |input: &[u8]| match parse_elves(input, Blanks::Strict) {
+            Err(err @ aoc2022::Error::Parse { .. }) => err.to_string(),
+            other => panic!("expected an error, got {other:?}"),
+        };
+
+        assert_eq!(
+            "line 1: blank line without an elf before it",
+            error(TEST_DOCUMENT)
+        );
+        assert_eq!(
+            "line 3: blank line without an elf before it",
+            error(b"100\n\n\n200\n")
+        );
+        assert_eq!(
+            "line 2: line contains only whitespace",
+            error(b"100\n  \n200\n")
+        );
+        assert_eq!(
+            "line 2: blank line without an elf after it",
+            error(b"100\n\n")
+        );
+        assert_eq!(
+            "line 2: invalid digit found in string",
+            error(b"100\n1x0\n")
         );
     }
 }
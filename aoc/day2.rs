@@ -1,19 +1,58 @@
 // https://adventofcode.com/2022/day/2
-use std::{io::Read, str::FromStr};
+use std::{fmt, io::Read, str::FromStr};
 
-use aoc2022::Part;
+use aoc2022::{Game, Mode, Options, Part};
+use itertools::Itertools;
 
-pub(crate) fn execute<R: Read>(part: &aoc2022::Part, mut reader: R) -> aoc2022::Result<()> {
+pub(crate) fn execute<R: Read>(
+    part: &aoc2022::Part,
+    options: &Options,
+    mut reader: R,
+) -> aoc2022::Result<()> {
     let mut raw = String::new();
     reader.read_to_string(&mut raw)?;
 
+    let rules = match options.game {
+        Game::RockPaperScissors => Rules::rock_paper_scissors(),
+        Game::RockPaperScissorsLizardSpock => Rules::rock_paper_scissors_lizard_spock(),
+    };
+
+    match options.mode {
+        Some(Mode::Infer) => {
+            let interpretations = match part {
+                Part::Part1 => infer_moves(&raw, &rules),
+                Part::Part2 => infer_outcomes(&raw, &rules),
+            }?;
+
+            print_interpretations(&interpretations);
+            return Ok(());
+        }
+        Some(Mode::Tournament) => {
+            let rounds = match part {
+                Part::Part1 => StrategyGuide1::parse(&raw, rules.clone())?.rounds(),
+                Part::Part2 => StrategyGuide2::parse(&raw, rules.clone())?.rounds(),
+            };
+
+            let tournament = Tournament::new(&rules, &rounds);
+            if options.per_round {
+                print!("{}", tournament.round_table(&rules));
+                println!();
+            }
+
+            print!("{tournament}");
+            return Ok(());
+        }
+        Some(mode) => return Err(aoc2022::Error::UnsupportedMode(mode)),
+        None => {}
+    };
+
     let score = match part {
         Part::Part1 => {
-            let guide: StrategyGuide1 = raw.parse().unwrap();
+            let guide = StrategyGuide1::parse(&raw, rules)?;
             guide.calculate_score()
         }
         Part::Part2 => {
-            let guide: StrategyGuide2 = raw.parse().unwrap();
+            let guide = StrategyGuide2::parse(&raw, rules)?;
             guide.calculate_score()
         }
     };
@@ -22,95 +61,338 @@ pub(crate) fn execute<R: Read>(part: &aoc2022::Part, mut reader: R) -> aoc2022::
     Ok(())
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq)]
 enum StrategyGuideError {
-    UnknownActionError,
-    UnknownOutcomeError,
+    UnknownAction { line: usize, token: String },
+    UnknownOutcome { line: usize, token: String },
+    MissingToken { line: usize },
+    UnexpectedToken { line: usize, token: String },
+}
+
+impl StrategyGuideError {
+    fn line(&self) -> usize {
+        match self {
+            Self::UnknownAction { line, .. }
+            | Self::UnknownOutcome { line, .. }
+            | Self::MissingToken { line }
+            | Self::UnexpectedToken { line, .. } => *line,
+        }
+    }
+
+    fn message(&self) -> String {
+        match self {
+            Self::UnknownAction { token, .. } => format!("unknown action '{token}'"),
+            Self::UnknownOutcome { token, .. } => format!("unknown outcome '{token}'"),
+            Self::MissingToken { .. } => "expected two letters separated by a space".into(),
+            Self::UnexpectedToken { token, .. } => {
+                format!("unexpected '{token}' after the second letter")
+            }
+        }
+    }
+}
+
+impl fmt::Display for StrategyGuideError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line(), self.message())
+    }
+}
+
+impl From<StrategyGuideError> for aoc2022::Error {
+    fn from(err: StrategyGuideError) -> Self {
+        aoc2022::Error::Parse {
+            line: err.line(),
+            message: err.message(),
+        }
+    }
+}
+
+impl UnknownActionError {
+    fn at(self, line: usize) -> StrategyGuideError {
+        StrategyGuideError::UnknownAction {
+            line,
+            token: self.0,
+        }
+    }
+}
+
+impl UnknownOutcomeError {
+    fn at(self, line: usize) -> StrategyGuideError {
+        StrategyGuideError::UnknownOutcome {
+            line,
+            token: self.0,
+        }
+    }
+}
+
+/// Splits each line of the guide into its two columns, skipping over blank lines.
+///
+/// Lines are numbered from 1.
+fn split_lines(s: &str) -> impl Iterator<Item = Result<(usize, &str, &str), StrategyGuideError>> {
+    s.lines().enumerate().filter_map(|(n, line)| {
+        let line_number = n + 1;
+        let mut tokens = line.split_whitespace();
+        let first = tokens.next()?;
+        let Some(second) = tokens.next() else {
+            return Some(Err(StrategyGuideError::MissingToken { line: line_number }));
+        };
+
+        if let Some(token) = tokens.next() {
+            return Some(Err(StrategyGuideError::UnexpectedToken {
+                line: line_number,
+                token: token.into(),
+            }));
+        }
+
+        Some(Ok((line_number, first, second)))
+    })
+}
+
+/// A single move that can be played in a game, and how it is written in the strategy guide.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Move {
+    name: String,
+    points: u32,
+    /// The letter used in the first column of the guide, for the opponent's move.
+    opponent_code: String,
+    /// The letter used in the second column of the guide, for our move.
+    own_code: String,
 }
 
-impl From<UnknownActionError> for StrategyGuideError {
-    fn from(_: UnknownActionError) -> Self {
-        Self::UnknownActionError
+impl Move {
+    fn new(name: &str, points: u32, opponent_code: &str, own_code: &str) -> Self {
+        Move {
+            name: name.into(),
+            points,
+            opponent_code: opponent_code.into(),
+            own_code: own_code.into(),
+        }
     }
 }
 
-impl From<UnknownOutcomeError> for StrategyGuideError {
-    fn from(_: UnknownOutcomeError) -> Self {
-        Self::UnknownOutcomeError
+#[derive(Debug, PartialEq, Eq)]
+struct InvalidRulesError;
+
+/// The rules of a game like Rock Paper Scissors; which moves exist, what they are worth, how they are written, and which moves beat which.
+///
+/// Rules must be balanced; every pair of distinct moves has exactly one winner, and every move beats exactly as many moves as it loses to.
+#[derive(Debug, Clone)]
+struct Rules {
+    moves: Vec<Move>,
+    /// beats[a][b] is true if move a beats move b.
+    beats: Vec<Vec<bool>>,
+}
+
+impl Rules {
+    /// Creates a rule set from a list of moves and the (winner, loser) pairs between them.
+    fn new(moves: Vec<Move>, beats: &[(usize, usize)]) -> Result<Self, InvalidRulesError> {
+        let n = moves.len();
+        if n.is_multiple_of(2) {
+            return Err(InvalidRulesError);
+        }
+
+        let mut table = vec![vec![false; n]; n];
+        for (winner, loser) in beats {
+            if *winner >= n || *loser >= n || winner == loser {
+                return Err(InvalidRulesError);
+            }
+
+            table[*winner][*loser] = true;
+        }
+
+        let rules = Rules {
+            moves,
+            beats: table,
+        };
+
+        for a in 0..n {
+            let wins = (0..n).filter(|b| rules.beats[a][*b]).count();
+            if wins != n / 2 {
+                return Err(InvalidRulesError);
+            }
+
+            if (0..n).any(|b| a != b && rules.beats[a][b] == rules.beats[b][a]) {
+                return Err(InvalidRulesError);
+            }
+        }
+
+        Ok(rules)
+    }
+
+    /// Creates a rule set where the moves are arranged in a circle, and each move beats the moves an odd number of steps behind it.
+    ///
+    /// This is how both Rock Paper Scissors and Rock Paper Scissors Lizard Spock are laid out.
+    fn cyclic(moves: Vec<Move>) -> Result<Self, InvalidRulesError> {
+        let n = moves.len();
+        let beats: Vec<(usize, usize)> = (0..n)
+            .flat_map(|a| (0..n).map(move |b| (a, b)))
+            .filter(|(a, b)| (a + n - b) % n % 2 == 1)
+            .collect();
+
+        Rules::new(moves, &beats)
+    }
+
+    /// The rules from the puzzle.
+    fn rock_paper_scissors() -> Self {
+        Rules::cyclic(vec![
+            Move::new("Rock", 1, "A", "X"),
+            Move::new("Paper", 2, "B", "Y"),
+            Move::new("Scissors", 3, "C", "Z"),
+        ])
+        .expect("rock paper scissors is balanced")
+    }
+
+    fn rock_paper_scissors_lizard_spock() -> Self {
+        Rules::cyclic(vec![
+            Move::new("Rock", 1, "A", "V"),
+            Move::new("Paper", 2, "B", "W"),
+            Move::new("Scissors", 3, "C", "X"),
+            Move::new("Spock", 4, "D", "Y"),
+            Move::new("Lizard", 5, "E", "Z"),
+        ])
+        .expect("rock paper scissors lizard spock is balanced")
+    }
+
+    fn actions(&self) -> impl Iterator<Item = Action> {
+        (0..self.moves.len()).map(Action)
+    }
+
+    fn points(&self, action: Action) -> u32 {
+        self.moves[action.0].points
+    }
+
+    /// Determines if  A won or lost the round based on the given actions.
+    fn outcome(&self, a: Action, b: Action) -> Outcome {
+        if a == b {
+            Outcome::Draw
+        } else if self.beats[a.0][b.0] {
+            Outcome::Win
+        } else {
+            Outcome::Loss
+        }
+    }
+
+    /// Picks the action that gets the desired outcome against the other action.
+    ///
+    /// With more than three moves there can be several candidates; the one worth the most points is played.
+    fn action_for_outcome(&self, other: Action, desired_outcome: Outcome) -> Action {
+        self.actions()
+            .filter(|action| self.outcome(*action, other) == desired_outcome)
+            .max_by_key(|action| (self.points(*action), std::cmp::Reverse(action.0)))
+            .expect("balanced rules have an action for every outcome")
+    }
+
+    fn parse_opponent_action(&self, s: &str) -> Result<Action, UnknownActionError> {
+        self.actions()
+            .find(|action| self.moves[action.0].opponent_code == s)
+            .ok_or_else(|| UnknownActionError(s.into()))
+    }
+
+    fn parse_own_action(&self, s: &str) -> Result<Action, UnknownActionError> {
+        self.actions()
+            .find(|action| self.moves[action.0].own_code == s)
+            .ok_or_else(|| UnknownActionError(s.into()))
     }
 }
 
-/// Determines if  A won or lost the round based on the given actions.
-fn determine_round_outcome(a: Action, b: Action) -> Outcome {
-    match (a, b) {
-        (a, b) if a == b => Outcome::Draw,
-        (Action::Paper, Action::Rock)
-        | (Action::Scissors, Action::Paper)
-        | (Action::Rock, Action::Scissors) => Outcome::Win,
-        _ => Outcome::Loss,
+impl Default for Rules {
+    fn default() -> Self {
+        Rules::rock_paper_scissors()
     }
 }
 
 struct StrategyGuide1 {
+    rules: Rules,
     actions: Vec<(Action, Action)>,
 }
 
 impl StrategyGuide1 {
-    fn new() -> Self {
+    fn new(rules: Rules) -> Self {
         Self {
+            rules,
             actions: Vec::new(),
         }
     }
-}
-
-impl FromStr for StrategyGuide1 {
-    type Err = StrategyGuideError;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut guide = StrategyGuide1::new();
-        for line in s.lines() {
-            let parts: Vec<&str> = line.split(' ').collect();
-            guide.actions.push((parts[0].parse()?, parts[1].parse()?));
+    fn parse(s: &str, rules: Rules) -> Result<Self, StrategyGuideError> {
+        let mut guide = StrategyGuide1::new(rules);
+        for line in split_lines(s) {
+            let (n, opponent, me) = line?;
+            let opponent = guide
+                .rules
+                .parse_opponent_action(opponent)
+                .map_err(|err| err.at(n))?;
+            let me = guide.rules.parse_own_action(me).map_err(|err| err.at(n))?;
+            guide.actions.push((opponent, me));
         }
 
         Ok(guide)
     }
 }
 
-fn calculate_action_for_desired_outcome(other: Action, desired_outcome: Outcome) -> Action {
-    match (other, desired_outcome) {
-        (Action::Rock, Outcome::Win) => Action::Paper,
-        (Action::Rock, Outcome::Loss) => Action::Scissors,
-
-        (Action::Scissors, Outcome::Win) => Action::Rock,
-        (Action::Scissors, Outcome::Loss) => Action::Paper,
-
-        (Action::Paper, Outcome::Win) => Action::Scissors,
-        (Action::Paper, Outcome::Loss) => Action::Rock,
+impl FromStr for StrategyGuide1 {
+    type Err = StrategyGuideError;
 
-        (a, Outcome::Draw) => a,
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        StrategyGuide1::parse(s, Rules::default())
     }
 }
 
 /// In the second version of the strategy guide, the actions parsed are instead (Action, Outcome), where Action is what the opponent will play, and Outcome is the outcome we want to get.
 struct StrategyGuide2 {
+    rules: Rules,
     actions: Vec<(Action, Outcome)>,
 }
 
 impl StrategyGuide2 {
-    fn calculate_round_score(desired_outcome: Outcome, opponent: Action) -> u32 {
+    fn parse(s: &str, rules: Rules) -> Result<Self, StrategyGuideError> {
+        let mut guide = StrategyGuide2 {
+            rules,
+            actions: Vec::new(),
+        };
+
+        for line in split_lines(s) {
+            let (n, opponent, outcome) = line?;
+            let opponent = guide
+                .rules
+                .parse_opponent_action(opponent)
+                .map_err(|err| err.at(n))?;
+            let outcome = outcome
+                .parse()
+                .map_err(|err: UnknownOutcomeError| err.at(n))?;
+            guide.actions.push((opponent, outcome));
+        }
+
+        Ok(guide)
+    }
+
+    /// Returns the (opponent, our) actions for each round, after working out what we need to play.
+    fn rounds(&self) -> Vec<(Action, Action)> {
+        self.actions
+            .iter()
+            .map(|(opponent, desired_outcome)| {
+                (
+                    *opponent,
+                    self.rules.action_for_outcome(*opponent, *desired_outcome),
+                )
+            })
+            .collect()
+    }
+
+    fn calculate_round_score(&self, desired_outcome: Outcome, opponent: Action) -> u32 {
         desired_outcome.u32()
-            + calculate_action_for_desired_outcome(opponent, desired_outcome).u32()
+            + self
+                .rules
+                .points(self.rules.action_for_outcome(opponent, desired_outcome))
     }
 
     /// Calculates the score of each action in the strategy guide.
     ///
-    /// The score is equal to a fixed amount for the shape you played (1 for Rock, 2 for Paper and 3 for Scissors), plus an additional amount based on the outcome of the round; 0 for a loss, 3 for a draw and 6 for a win.
+    /// The score is equal to a fixed amount for the shape you played (1 for Rock, 2 for Paper and 3 for Scissors, or whatever the rules say), plus an additional amount based on the outcome of the round; 0 for a loss, 3 for a draw and 6 for a win.
     fn calculate_score(&self) -> u32 {
         self.actions
             .iter()
             .fold(0, |score, (opponent, desired_outcome)| {
-                score + Self::calculate_round_score(*desired_outcome, *opponent)
+                score + self.calculate_round_score(*desired_outcome, *opponent)
             })
     }
 }
@@ -119,60 +401,261 @@ impl FromStr for StrategyGuide2 {
     type Err = StrategyGuideError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut guide = StrategyGuide2 {
-            actions: Vec::new(),
-        };
-
-        for line in s.lines() {
-            let parts: Vec<&str> = line.split(' ').collect();
-            guide.actions.push((parts[0].parse()?, parts[1].parse()?));
-        }
-
-        Ok(guide)
+        StrategyGuide2::parse(s, Rules::default())
     }
 }
 
 impl StrategyGuide1 {
-    fn calculate_round_score(me: Action, opponent: Action) -> u32 {
-        me.u32() + determine_round_outcome(me, opponent).u32()
+    /// Returns the (opponent, our) actions for each round.
+    fn rounds(&self) -> Vec<(Action, Action)> {
+        self.actions.clone()
+    }
+
+    fn calculate_round_score(&self, me: Action, opponent: Action) -> u32 {
+        self.rules.points(me) + self.rules.outcome(me, opponent).u32()
     }
 
     /// Calculates the score of each action in the strategy guide.
     ///
-    /// The score is equal to a fixed amount for the shape you played (1 for Rock, 2 for Paper and 3 for Scissors), plus an additional amount based on the outcome of the round; 0 for a loss, 3 for a draw and 6 for a win.
+    /// The score is equal to a fixed amount for the shape you played (1 for Rock, 2 for Paper and 3 for Scissors, or whatever the rules say), plus an additional amount based on the outcome of the round; 0 for a loss, 3 for a draw and 6 for a win.
     fn calculate_score(&self) -> u32 {
         self.actions.iter().fold(0, |score, (opponent, me)| {
-            score + Self::calculate_round_score(*me, *opponent)
+            score + self.calculate_round_score(*me, *opponent)
         })
     }
 }
 
-#[derive(Debug)]
-struct UnknownActionError;
+/// A single round of a tournament, scored from both sides.
+#[derive(Debug, PartialEq, Eq)]
+struct Round {
+    opponent: Action,
+    me: Action,
+    /// The outcome from our point of view.
+    outcome: Outcome,
+    opponent_score: u32,
+    my_score: u32,
+    /// Our total minus the opponent's total, after this round.
+    difference: i64,
+}
 
-#[derive(Eq, PartialEq, Debug, Copy, Clone)]
-enum Action {
-    Rock = 1,
-    Paper = 2,
-    Scissors = 3,
+/// Every round of the guide played out, with the scores of both players.
+#[derive(Debug, PartialEq, Eq)]
+struct Tournament {
+    rounds: Vec<Round>,
 }
 
-impl Action {
-    fn u32(&self) -> u32 {
-        *self as u32
+impl Tournament {
+    fn new(rules: &Rules, actions: &[(Action, Action)]) -> Self {
+        let mut difference = 0;
+        let rounds = actions
+            .iter()
+            .map(|(opponent, me)| {
+                let my_score = rules.points(*me) + rules.outcome(*me, *opponent).u32();
+                let opponent_score = rules.points(*opponent) + rules.outcome(*opponent, *me).u32();
+                difference += my_score as i64 - opponent_score as i64;
+                Round {
+                    opponent: *opponent,
+                    me: *me,
+                    outcome: rules.outcome(*me, *opponent),
+                    opponent_score,
+                    my_score,
+                    difference,
+                }
+            })
+            .collect();
+
+        Tournament { rounds }
     }
-}
 
-impl FromStr for Action {
-    type Err = UnknownActionError;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "A" | "X" => Ok(Action::Rock),
-            "B" | "Y" => Ok(Action::Paper),
-            "C" | "Z" => Ok(Action::Scissors),
-            _ => Err(Self::Err {}),
+    fn my_total(&self) -> u32 {
+        self.rounds.iter().map(|round| round.my_score).sum()
+    }
+
+    fn opponent_total(&self) -> u32 {
+        self.rounds.iter().map(|round| round.opponent_score).sum()
+    }
+
+    fn count(&self, outcome: Outcome) -> usize {
+        self.rounds
+            .iter()
+            .filter(|round| round.outcome == outcome)
+            .count()
+    }
+
+    /// Our biggest lead and biggest deficit at the end of any round.
+    fn extremes(&self) -> (i64, i64) {
+        self.rounds.iter().fold((0, 0), |(lead, deficit), round| {
+            (lead.max(round.difference), deficit.min(round.difference))
+        })
+    }
+
+    fn round_table(&self, rules: &Rules) -> String {
+        let mut table = format!(
+            "{:>6} {:<10} {:<10} {:<5} {:>5} {:>5} {:>6}\n",
+            "round", "them", "us", "", "them", "us", "diff"
+        );
+
+        for (n, round) in self.rounds.iter().enumerate() {
+            table.push_str(&format!(
+                "{:>6} {:<10} {:<10} {:<5} {:>5} {:>5} {:>+6}\n",
+                n + 1,
+                rules.moves[round.opponent.0].name,
+                rules.moves[round.me.0].name,
+                format!("{:?}", round.outcome),
+                round.opponent_score,
+                round.my_score,
+                round.difference
+            ));
         }
+
+        table
+    }
+}
+
+impl fmt::Display for Tournament {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (lead, deficit) = self.extremes();
+        let (wins, draws, losses) = (
+            self.count(Outcome::Win),
+            self.count(Outcome::Draw),
+            self.count(Outcome::Loss),
+        );
+
+        writeln!(f, "{:<16}{:>10}{:>10}", "", "them", "us")?;
+        writeln!(
+            f,
+            "{:<16}{:>10}{:>10}",
+            "score",
+            self.opponent_total(),
+            self.my_total()
+        )?;
+        writeln!(f, "{:<16}{:>10}{:>10}", "wins", losses, wins)?;
+        writeln!(f, "{:<16}{:>10}{:>10}", "draws", draws, draws)?;
+        writeln!(f, "{:<16}{:>10}{:>10}", "losses", wins, losses)?;
+        writeln!(f)?;
+        writeln!(f, "{:<16}{}", "rounds", self.rounds.len())?;
+        writeln!(
+            f,
+            "{:<16}{:+}",
+            "difference",
+            self.rounds.last().map_or(0, |round| round.difference)
+        )?;
+        writeln!(f, "{:<16}{:+}", "biggest lead", lead)?;
+        writeln!(f, "{:<16}{:+}", "biggest deficit", deficit)
+    }
+}
+
+/// One way of reading the letters in the second column of the guide, and the score we'd get by following it.
+#[derive(Debug, PartialEq, Eq)]
+struct Interpretation {
+    /// (letter, meaning) pairs.
+    mapping: Vec<(String, String)>,
+    score: u32,
+}
+
+/// Scores the guide under every assignment of our letters to moves, in the style of part 1.
+///
+/// The guide is parsed once with the letters read as in the puzzle, and each permutation then remaps the parsed actions.
+fn infer_moves(raw: &str, rules: &Rules) -> Result<Vec<Interpretation>, StrategyGuideError> {
+    let mut guide = StrategyGuide1::parse(raw, rules.clone())?;
+    let parsed = guide.actions.clone();
+    let mut interpretations = Vec::new();
+    for permutation in rules.actions().permutations(rules.moves.len()) {
+        guide.actions = parsed
+            .iter()
+            .map(|(opponent, me)| (*opponent, permutation[me.0]))
+            .collect();
+
+        interpretations.push(Interpretation {
+            mapping: rules
+                .moves
+                .iter()
+                .zip(&permutation)
+                .map(|(m, action)| (m.own_code.clone(), rules.moves[action.0].name.clone()))
+                .collect(),
+            score: guide.calculate_score(),
+        });
+    }
+
+    Ok(interpretations)
+}
+
+/// Scores the guide under every assignment of our letters to outcomes, in the style of part 2.
+///
+/// As with moves, the guide is parsed once and each permutation remaps the parsed outcomes.
+fn infer_outcomes(raw: &str, rules: &Rules) -> Result<Vec<Interpretation>, StrategyGuideError> {
+    let mut guide = StrategyGuide2::parse(raw, rules.clone())?;
+    let parsed = guide.actions.clone();
+    let mut interpretations = Vec::new();
+    for permutation in Outcome::ALL.into_iter().permutations(Outcome::ALL.len()) {
+        let remap = |outcome: Outcome| {
+            let index = Outcome::ALL.iter().position(|o| *o == outcome);
+            permutation[index.expect("every outcome is listed")]
+        };
+
+        guide.actions = parsed
+            .iter()
+            .map(|(opponent, outcome)| (*opponent, remap(*outcome)))
+            .collect();
+
+        interpretations.push(Interpretation {
+            mapping: ["X", "Y", "Z"]
+                .iter()
+                .zip(&permutation)
+                .map(|(letter, outcome)| (letter.to_string(), format!("{outcome:?}")))
+                .collect(),
+            score: guide.calculate_score(),
+        });
+    }
+
+    Ok(interpretations)
+}
+
+/// Returns the interpretation that gives us the highest score, preferring the earliest listed if several tie.
+fn best_interpretation(interpretations: &[Interpretation]) -> Option<&Interpretation> {
+    interpretations
+        .iter()
+        .rev()
+        .max_by_key(|interpretation| interpretation.score)
+}
+
+fn format_mapping(interpretation: &Interpretation) -> String {
+    interpretation
+        .mapping
+        .iter()
+        .map(|(letter, meaning)| format!("{letter}={meaning}"))
+        .join(" ")
+}
+
+fn print_interpretations(interpretations: &[Interpretation]) {
+    let best = best_interpretation(interpretations);
+    for interpretation in interpretations {
+        let marker = if Some(interpretation) == best {
+            "*"
+        } else {
+            " "
+        };
+        let mapping = format_mapping(interpretation);
+        println!("{marker} {mapping:<40} {}", interpretation.score);
     }
+
+    if let Some(best) = best {
+        println!("\nbest: {} ({})", format_mapping(best), best.score);
+    }
+}
+
+#[derive(Debug)]
+struct UnknownActionError(String);
+
+/// A move, identified by its position in the rules being played.
+#[derive(Eq, PartialEq, Debug, Copy, Clone)]
+struct Action(usize);
+
+#[cfg(test)]
+impl Action {
+    const ROCK: Action = Action(0);
+    const PAPER: Action = Action(1);
+    const SCISSORS: Action = Action(2);
 }
 
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
@@ -183,9 +666,12 @@ enum Outcome {
 }
 
 #[derive(Debug)]
-struct UnknownOutcomeError;
+struct UnknownOutcomeError(String);
 
 impl Outcome {
+    /// Every outcome, in the order of the letters X, Y and Z that stand for them.
+    const ALL: [Outcome; 3] = [Outcome::Loss, Outcome::Draw, Outcome::Win];
+
     fn u32(&self) -> u32 {
         *self as u32
     }
@@ -198,7 +684,7 @@ impl FromStr for Outcome {
             "X" => Ok(Outcome::Loss),
             "Y" => Ok(Outcome::Draw),
             "Z" => Ok(Outcome::Win),
-            _ => Err(Self::Err {}),
+            s => Err(UnknownOutcomeError(s.into())),
         }
     }
 }
@@ -206,40 +692,30 @@ impl FromStr for Outcome {
 #[cfg(test)]
 mod tests {
     use crate::day2::{
-        calculate_action_for_desired_outcome, determine_round_outcome, Action, Outcome,
-        StrategyGuide1,
+        best_interpretation, infer_moves, infer_outcomes, Action, Interpretation, Move, Outcome,
+        Rules, StrategyGuide1, StrategyGuideError, Tournament,
     };
 
     use super::StrategyGuide2;
 
     #[test]
     fn has_correct_scores() {
-        assert_eq!(Action::Rock.u32(), 1);
-        assert_eq!(Action::Paper.u32(), 2);
-        assert_eq!(Action::Scissors.u32(), 3);
+        let rules = Rules::default();
+        assert_eq!(rules.points(Action::ROCK), 1);
+        assert_eq!(rules.points(Action::PAPER), 2);
+        assert_eq!(rules.points(Action::SCISSORS), 3);
         assert_eq!(Outcome::Win.u32(), 6);
         assert_eq!(Outcome::Draw.u32(), 3);
         assert_eq!(Outcome::Loss.u32(), 0);
 
+        assert_eq!(rules.outcome(Action::PAPER, Action::ROCK), Outcome::Win);
+        assert_eq!(rules.outcome(Action::ROCK, Action::PAPER), Outcome::Loss);
+        assert_eq!(rules.outcome(Action::SCISSORS, Action::PAPER), Outcome::Win);
         assert_eq!(
-            determine_round_outcome(Action::Paper, Action::Rock),
-            Outcome::Win
-        );
-
-        assert_eq!(
-            determine_round_outcome(Action::Rock, Action::Paper),
-            Outcome::Loss
-        );
-
-        assert_eq!(
-            determine_round_outcome(Action::Scissors, Action::Paper),
-            Outcome::Win
-        );
-
-        assert_eq!(
-            determine_round_outcome(Action::Paper, Action::Scissors),
+            rules.outcome(Action::PAPER, Action::SCISSORS),
             Outcome::Loss
         );
+        assert_eq!(rules.outcome(Action::ROCK, Action::SCISSORS), Outcome::Win);
     }
 
     #[test]
@@ -249,9 +725,9 @@ B X
 C Z";
 
         let guide: StrategyGuide1 = raw.parse().expect("Failed to parse");
-        assert_eq!(guide.actions[0], (Action::Rock, Action::Paper));
-        assert_eq!(guide.actions[1], (Action::Paper, Action::Rock));
-        assert_eq!(guide.actions[2], (Action::Scissors, Action::Scissors));
+        assert_eq!(guide.actions[0], (Action::ROCK, Action::PAPER));
+        assert_eq!(guide.actions[1], (Action::PAPER, Action::ROCK));
+        assert_eq!(guide.actions[2], (Action::SCISSORS, Action::SCISSORS));
     }
 
     #[test]
@@ -265,19 +741,20 @@ C Z";
 
     #[test]
     fn correctly_determines_action_for_desired_outcome() {
+        let rules = Rules::default();
         assert_eq!(
-            Action::Scissors,
-            calculate_action_for_desired_outcome(Action::Paper, Outcome::Win)
+            Action::SCISSORS,
+            rules.action_for_outcome(Action::PAPER, Outcome::Win)
         );
 
         assert_eq!(
-            Action::Scissors,
-            calculate_action_for_desired_outcome(Action::Scissors, Outcome::Draw)
+            Action::SCISSORS,
+            rules.action_for_outcome(Action::SCISSORS, Outcome::Draw)
         );
 
         assert_eq!(
-            Action::Scissors,
-            calculate_action_for_desired_outcome(Action::Rock, Outcome::Loss)
+            Action::SCISSORS,
+            rules.action_for_outcome(Action::ROCK, Outcome::Loss)
         );
     }
 
@@ -289,4 +766,185 @@ C Z";
         let guide: StrategyGuide2 = raw.parse().unwrap();
         assert_eq!(guide.calculate_score(), 12);
     }
+
+    #[test]
+    fn lizard_spock_rules_are_balanced() {
+        let rules = Rules::rock_paper_scissors_lizard_spock();
+        let (rock, paper, scissors, spock, lizard) =
+            (Action(0), Action(1), Action(2), Action(3), Action(4));
+        assert_eq!(rules.outcome(scissors, paper), Outcome::Win);
+        assert_eq!(rules.outcome(paper, rock), Outcome::Win);
+        assert_eq!(rules.outcome(rock, lizard), Outcome::Win);
+        assert_eq!(rules.outcome(lizard, spock), Outcome::Win);
+        assert_eq!(rules.outcome(spock, scissors), Outcome::Win);
+        assert_eq!(rules.outcome(scissors, lizard), Outcome::Win);
+        assert_eq!(rules.outcome(lizard, paper), Outcome::Win);
+        assert_eq!(rules.outcome(paper, spock), Outcome::Win);
+        assert_eq!(rules.outcome(spock, rock), Outcome::Win);
+        assert_eq!(rules.outcome(rock, scissors), Outcome::Win);
+        assert_eq!(rules.outcome(rock, spock), Outcome::Loss);
+        // Rock is beaten by both paper and spock; spock is worth more.
+        assert_eq!(rules.action_for_outcome(rock, Outcome::Win), spock);
+    }
+
+    #[test]
+    fn scores_lizard_spock_guides() {
+        let rules = Rules::rock_paper_scissors_lizard_spock();
+        let raw = "A W
+E V
+D Y";
+        // Paper beats rock (2 + 6), rock crushes lizard (1 + 6), spock draws (4 + 3).
+        let guide = StrategyGuide1::parse(raw, rules.clone()).unwrap();
+        assert_eq!(guide.calculate_score(), 22);
+
+        // Spock beats rock (4 + 6), lizard loses to rock (5 + 0) and spock draws (4 + 3).
+        let guide = StrategyGuide2::parse("A Z\nA X\nD Y", rules).unwrap();
+        assert_eq!(guide.calculate_score(), 22);
+    }
+
+    #[test]
+    fn rejects_unbalanced_rules() {
+        let moves = vec![
+            Move::new("Rock", 1, "A", "W"),
+            Move::new("Paper", 2, "B", "X"),
+            Move::new("Scissors", 3, "C", "Y"),
+            Move::new("Well", 4, "D", "Z"),
+        ];
+
+        assert!(Rules::cyclic(moves.clone()).is_err());
+        assert!(Rules::new(moves[..3].to_vec(), &[(1, 0), (2, 1)]).is_err());
+        assert!(Rules::new(moves[..3].to_vec(), &[(1, 0), (2, 1), (0, 2)]).is_ok());
+    }
+
+    fn mapping(interpretation: &Interpretation) -> Vec<(&str, &str)> {
+        interpretation
+            .mapping
+            .iter()
+            .map(|(letter, meaning)| (letter.as_str(), meaning.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn infers_every_move_mapping() {
+        let raw = "A Y
+B X
+C Z";
+        let interpretations = infer_moves(raw, &Rules::default()).unwrap();
+        assert_eq!(6, interpretations.len());
+        // The first permutation is the identity, which is the reading from the puzzle.
+        assert_eq!(15, interpretations[0].score);
+        assert_eq!(
+            vec![("X", "Rock"), ("Y", "Paper"), ("Z", "Scissors")],
+            mapping(&interpretations[0])
+        );
+
+        // Winning every round; paper beats rock (2 + 6), scissors beats paper (3 + 6) and rock beats scissors (1 + 6).
+        let best = best_interpretation(&interpretations).unwrap();
+        assert_eq!(
+            vec![("X", "Scissors"), ("Y", "Paper"), ("Z", "Rock")],
+            mapping(best)
+        );
+        assert_eq!(
+            Some(best.score),
+            interpretations.iter().map(|i| i.score).max()
+        );
+    }
+
+    #[test]
+    fn infers_every_outcome_mapping() {
+        let raw = "A Y
+B X
+C Z";
+        let interpretations = infer_outcomes(raw, &Rules::default()).unwrap();
+        assert_eq!(6, interpretations.len());
+        assert_eq!(12, interpretations[0].score);
+        assert_eq!(
+            vec![("X", "Loss"), ("Y", "Draw"), ("Z", "Win")],
+            mapping(&interpretations[0])
+        );
+
+        // Losing to rock with scissors (3 + 0), beating paper with scissors (3 + 6) and drawing with scissors (3 + 3).
+        let best = best_interpretation(&interpretations).unwrap();
+        assert_eq!(18, best.score);
+        assert_eq!(
+            vec![("X", "Win"), ("Y", "Loss"), ("Z", "Draw")],
+            mapping(best)
+        );
+    }
+
+    #[test]
+    fn reports_where_the_guide_is_malformed() {
+        let error = |raw: &str| {
+            StrategyGuide1::parse(raw, Rules::default())
+                .err()
+                .map(|err| err.to_string())
+        };
+        assert_eq!(
+            Some("line 2: unknown action 'Q'".into()),
+            error("A Y\nB Q\n")
+        );
+        assert_eq!(Some("line 1: unknown action 'D'".into()), error("D Y\n"));
+        assert_eq!(
+            Some("line 3: expected two letters separated by a space".into()),
+            error("A Y\n\nB\n")
+        );
+        assert_eq!(
+            Some("line 1: unexpected 'Z' after the second letter".into()),
+            error("A Y Z\n")
+        );
+
+        let error = StrategyGuide2::parse("A Y\nC Q", Rules::default()).err();
+        assert_eq!(
+            Some(StrategyGuideError::UnknownOutcome {
+                line: 2,
+                token: "Q".into()
+            }),
+            error
+        );
+        assert_eq!(
+            aoc2022::Error::Parse {
+                line: 2,
+                message: "unknown outcome 'Q'".into()
+            },
+            error.unwrap().into()
+        );
+    }
+
+    #[test]
+    fn skips_blank_lines() {
+        let guide: StrategyGuide1 = "A Y\n\nB X\nC Z\n\n".parse().unwrap();
+        assert_eq!(guide.calculate_score(), 15);
+    }
+
+    #[test]
+    fn scores_tournaments_for_both_players() {
+        let raw = "A Y
+B X
+C Z";
+        let rules = Rules::default();
+        let guide: StrategyGuide1 = raw.parse().unwrap();
+        let tournament = Tournament::new(&rules, &guide.rounds());
+        assert_eq!(guide.calculate_score(), tournament.my_total());
+        // Rock loses to paper (1 + 0), paper beats rock (2 + 6) and scissors draws (3 + 3).
+        assert_eq!(15, tournament.opponent_total());
+        assert_eq!(
+            vec![7, 0, 0],
+            tournament
+                .rounds
+                .iter()
+                .map(|round| round.difference)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!((7, 0), tournament.extremes());
+        assert_eq!(1, tournament.count(Outcome::Win));
+        assert_eq!(1, tournament.count(Outcome::Loss));
+        assert_eq!(1, tournament.count(Outcome::Draw));
+
+        let guide: StrategyGuide2 = raw.parse().unwrap();
+        let tournament = Tournament::new(&rules, &guide.rounds());
+        assert_eq!(guide.calculate_score(), tournament.my_total());
+        // Rock draws with rock (1 + 3), paper beats rock (2 + 6) and scissors loses to rock (3 + 0).
+        assert_eq!(15, tournament.opponent_total());
+        assert_eq!((0, -7), tournament.extremes());
+    }
 }
@@ -1,7 +1,536 @@
-// https://adventofcode.com/2022/day/2
-use aoc2022::{Part, Result};
-use std::io::Read;
+// https://adventofcode.com/2022/day/22
+use aoc2022::{Error, Part, Result};
+use std::{
+    collections::{HashMap, HashSet},
+    io::Read,
+    str::FromStr,
+};
 
-pub(crate) fn execute<R: Read>(_part: &Part, mut _reader: R) -> Result<()> {
-    unimplemented!();
+pub(crate) fn execute<R: Read>(part: &Part, mut reader: R) -> Result<()> {
+    let mut raw = String::new();
+    reader.read_to_string(&mut raw)?;
+
+    let notes: Notes = raw.parse()?;
+    let password = match part {
+        Part::Part1 => notes.walk(&FlatWrapping::new(&notes.board)),
+        Part::Part2 => notes.walk(&CubeWrapping::new(&notes.board)?),
+    };
+
+    println!("{password}");
+    Ok(())
+}
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+enum Tile {
+    Void,
+    Open,
+    Wall,
+}
+
+/// Facings are numbered in the same order the puzzle uses for the final password: right, down, left, up.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+enum Facing {
+    Right = 0,
+    Down = 1,
+    Left = 2,
+    Up = 3,
+}
+
+impl Facing {
+    const ALL: [Facing; 4] = [Facing::Right, Facing::Down, Facing::Left, Facing::Up];
+
+    fn turn(self, turn: Turn) -> Self {
+        let offset = match turn {
+            Turn::Clockwise => 1,
+            Turn::Counterclockwise => 3,
+        };
+
+        Self::ALL[(self as usize + offset) % 4]
+    }
+
+    fn delta(self) -> (i64, i64) {
+        match self {
+            Facing::Right => (1, 0),
+            Facing::Down => (0, 1),
+            Facing::Left => (-1, 0),
+            Facing::Up => (0, -1),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+enum Turn {
+    Clockwise,
+    Counterclockwise,
+}
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+enum Instruction {
+    Forward(u32),
+    Turn(Turn),
+}
+
+/// (column, row), both zero-based.
+type Position = (usize, usize);
+
+#[derive(Debug)]
+struct Board {
+    rows: Vec<Vec<Tile>>,
+    width: usize,
+}
+
+impl Board {
+    /// Returns the tile at the given position, treating anything outside of the drawn area as void.
+    fn tile(&self, x: i64, y: i64) -> Tile {
+        if x < 0 || y < 0 {
+            return Tile::Void;
+        }
+
+        self.rows
+            .get(y as usize)
+            .and_then(|row| row.get(x as usize))
+            .copied()
+            .unwrap_or(Tile::Void)
+    }
+
+    fn start(&self) -> Option<Position> {
+        let x = self.rows.first()?.iter().position(|t| *t == Tile::Open)?;
+        Some((x, 0))
+    }
+
+    fn tile_count(&self) -> usize {
+        self.rows
+            .iter()
+            .flatten()
+            .filter(|t| **t != Tile::Void)
+            .count()
+    }
+}
+
+impl FromStr for Board {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut rows = Vec::new();
+        for line in s.lines() {
+            let row = line
+                .chars()
+                .map(|c| match c {
+                    ' ' => Ok(Tile::Void),
+                    '.' => Ok(Tile::Open),
+                    '#' => Ok(Tile::Wall),
+                    c => Err(Error::InvalidInput(format!("unknown tile '{c}'"))),
+                })
+                .collect::<Result<Vec<Tile>>>()?;
+            rows.push(row);
+        }
+
+        let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+        Ok(Board { rows, width })
+    }
+}
+
+fn parse_path(s: &str) -> Result<Vec<Instruction>> {
+    let mut instructions = Vec::new();
+    let mut digits = String::new();
+    for c in s.trim().chars() {
+        if c.is_ascii_digit() {
+            digits.push(c);
+            continue;
+        }
+
+        if !digits.is_empty() {
+            instructions.push(Instruction::Forward(digits.parse()?));
+            digits.clear();
+        }
+
+        let turn = match c {
+            'R' => Turn::Clockwise,
+            'L' => Turn::Counterclockwise,
+            c => return Err(Error::InvalidInput(format!("unknown instruction '{c}'"))),
+        };
+
+        instructions.push(Instruction::Turn(turn));
+    }
+
+    if !digits.is_empty() {
+        instructions.push(Instruction::Forward(digits.parse()?));
+    }
+
+    Ok(instructions)
+}
+
+/// The monkeys' notes; a board followed by a blank line and the path to walk.
+#[derive(Debug)]
+struct Notes {
+    board: Board,
+    path: Vec<Instruction>,
+}
+
+impl FromStr for Notes {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        // The board itself can begin with whitespace, so only leading newlines are skipped.
+        let s = s.trim_start_matches(['\n', '\r']);
+        let (board, path) = s
+            .split_once("\n\n")
+            .ok_or_else(|| Error::InvalidInput("missing path after board".into()))?;
+
+        Ok(Notes {
+            board: board.parse()?,
+            path: parse_path(path)?,
+        })
+    }
+}
+
+impl Notes {
+    /// Walks the path from the starting tile and returns the final password.
+    fn walk<W: Wrapping>(&self, wrapping: &W) -> usize {
+        let Some(mut position) = self.board.start() else {
+            return 0;
+        };
+
+        let mut facing = Facing::Right;
+        for instruction in &self.path {
+            match instruction {
+                Instruction::Turn(turn) => facing = facing.turn(*turn),
+                Instruction::Forward(n) => {
+                    for _ in 0..*n {
+                        let (next, next_facing) = self.step(wrapping, position, facing);
+                        if self.board.tile(next.0 as i64, next.1 as i64) == Tile::Wall {
+                            break;
+                        }
+
+                        position = next;
+                        facing = next_facing;
+                    }
+                }
+            }
+        }
+
+        1000 * (position.1 + 1) + 4 * (position.0 + 1) + facing as usize
+    }
+
+    fn step<W: Wrapping>(
+        &self,
+        wrapping: &W,
+        (x, y): Position,
+        facing: Facing,
+    ) -> (Position, Facing) {
+        let (dx, dy) = facing.delta();
+        let (nx, ny) = (x as i64 + dx, y as i64 + dy);
+        if self.board.tile(nx, ny) == Tile::Void {
+            wrapping.wrap((x, y), facing)
+        } else {
+            ((nx as usize, ny as usize), facing)
+        }
+    }
+}
+
+/// Decides where you end up when you walk off the edge of the board.
+trait Wrapping {
+    fn wrap(&self, position: Position, facing: Facing) -> (Position, Facing);
+}
+
+/// Part 1: walking off an edge brings you back around to the opposite side of the same row or column.
+struct FlatWrapping<'a> {
+    board: &'a Board,
+}
+
+impl<'a> FlatWrapping<'a> {
+    fn new(board: &'a Board) -> Self {
+        Self { board }
+    }
+}
+
+impl Wrapping for FlatWrapping<'_> {
+    fn wrap(&self, (x, y): Position, facing: Facing) -> (Position, Facing) {
+        let (dx, dy) = facing.delta();
+        // Walk backwards until we fall off the other side.
+        let (mut cx, mut cy) = (x as i64, y as i64);
+        while self.board.tile(cx - dx, cy - dy) != Tile::Void {
+            cx -= dx;
+            cy -= dy;
+        }
+
+        ((cx as usize, cy as usize), facing)
+    }
+}
+
+type Vec3 = [i64; 3];
+
+fn dot(a: Vec3, b: Vec3) -> i64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn neg(a: Vec3) -> Vec3 {
+    [-a[0], -a[1], -a[2]]
+}
+
+fn add(a: Vec3, b: Vec3) -> Vec3 {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+fn scale(a: Vec3, k: i64) -> Vec3 {
+    [a[0] * k, a[1] * k, a[2] * k]
+}
+
+/// Where a face of the net ended up once folded; the outward normal, and the directions that the net's x and y axes point in.
+#[derive(Debug, Copy, Clone)]
+struct Frame {
+    normal: Vec3,
+    right: Vec3,
+    down: Vec3,
+}
+
+impl Frame {
+    fn direction(&self, facing: Facing) -> Vec3 {
+        match facing {
+            Facing::Right => self.right,
+            Facing::Down => self.down,
+            Facing::Left => neg(self.right),
+            Facing::Up => neg(self.down),
+        }
+    }
+
+    /// Folds the net along the edge in the given direction, returning the frame of the face on the other side.
+    fn fold(&self, facing: Facing) -> Frame {
+        let Frame {
+            normal,
+            right,
+            down,
+        } = *self;
+        match facing {
+            Facing::Right => Frame {
+                normal: right,
+                right: neg(normal),
+                down,
+            },
+            Facing::Left => Frame {
+                normal: neg(right),
+                right: normal,
+                down,
+            },
+            Facing::Down => Frame {
+                normal: down,
+                right,
+                down: neg(normal),
+            },
+            Facing::Up => Frame {
+                normal: neg(down),
+                right,
+                down: normal,
+            },
+        }
+    }
+}
+
+/// Part 2: the board is the net of a cube, and walking off an edge continues onto whichever face is adjacent once folded.
+///
+/// The folding is derived from the layout of the net rather than hard-coded, by walking the faces that touch in the net and rotating each neighbour into place.
+/// Every tile is then given a point in 3D space on a cube spanning -size..=size, using odd coordinates so that the centre of a tile never lies on an edge.
+struct CubeWrapping {
+    size: usize,
+    /// Face frames keyed by the face's (column, row) in the net.
+    faces: HashMap<Position, Frame>,
+}
+
+impl CubeWrapping {
+    fn new(board: &Board) -> Result<Self> {
+        let tiles = board.tile_count();
+        let size = (1..=tiles)
+            .take_while(|n| 6 * n * n <= tiles)
+            .find(|n| 6 * n * n == tiles)
+            .ok_or_else(|| Error::InvalidInput("board is not the net of a cube".into()))?;
+
+        let is_face =
+            |fx: usize, fy: usize| board.tile((fx * size) as i64, (fy * size) as i64) != Tile::Void;
+
+        let start = (0..board.width / size)
+            .find(|fx| is_face(*fx, 0))
+            .ok_or_else(|| Error::InvalidInput("board has no faces".into()))?;
+
+        let mut faces = HashMap::new();
+        let mut queue = vec![(
+            (start, 0),
+            Frame {
+                normal: [0, 0, -1],
+                right: [1, 0, 0],
+                down: [0, 1, 0],
+            },
+        )];
+
+        while let Some(((fx, fy), frame)) = queue.pop() {
+            if faces.contains_key(&(fx, fy)) {
+                continue;
+            }
+
+            faces.insert((fx, fy), frame);
+            for facing in Facing::ALL {
+                let (dx, dy) = facing.delta();
+                let (nx, ny) = (fx as i64 + dx, fy as i64 + dy);
+                if nx < 0 || ny < 0 || !is_face(nx as usize, ny as usize) {
+                    continue;
+                }
+
+                queue.push(((nx as usize, ny as usize), frame.fold(facing)));
+            }
+        }
+
+        if faces.len() != 6 {
+            return Err(Error::InvalidInput(format!(
+                "expected 6 connected faces, found {}",
+                faces.len()
+            )));
+        }
+
+        // Six connected faces can still fold up with two of them on the same side, like a 2x3 block.
+        let normals: HashSet<Vec3> = faces.values().map(|frame| frame.normal).collect();
+        if normals.len() != 6 {
+            return Err(Error::InvalidInput("board is not the net of a cube".into()));
+        }
+
+        Ok(CubeWrapping { size, faces })
+    }
+
+    fn face_of(&self, (x, y): Position) -> Frame {
+        self.faces[&(x / self.size, y / self.size)]
+    }
+}
+
+impl Wrapping for CubeWrapping {
+    fn wrap(&self, (x, y): Position, facing: Facing) -> (Position, Facing) {
+        let n = self.size as i64;
+        let frame = self.face_of((x, y));
+        let (col, row) = ((x % self.size) as i64, (y % self.size) as i64);
+
+        let point = add(
+            scale(frame.normal, n),
+            add(
+                scale(frame.right, 2 * col + 1 - n),
+                scale(frame.down, 2 * row + 1 - n),
+            ),
+        );
+
+        // Roll the point over the edge; one step further along the direction of travel, and one step down the side of the cube.
+        let direction = frame.direction(facing);
+        let point = add(add(point, direction), neg(frame.normal));
+
+        let ((tx, ty), target) = self
+            .faces
+            .iter()
+            .find(|(_, f)| f.normal == direction)
+            .map(|(face, f)| (*face, *f))
+            .expect("every direction on a cube leads to a face");
+
+        let col = ((dot(point, target.right) + n - 1) / 2) as usize;
+        let row = ((dot(point, target.down) + n - 1) / 2) as usize;
+        let heading = neg(frame.normal);
+        let facing = Facing::ALL
+            .into_iter()
+            .find(|f| target.direction(*f) == heading)
+            .expect("heading lies in the plane of the target face");
+
+        ((tx * self.size + col, ty * self.size + row), facing)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CubeWrapping, Facing, FlatWrapping, Instruction, Notes, Tile, Turn};
+
+    const SAMPLE: &str = "        ...#
+        .#..
+        #...
+        ....
+...#.......#
+........#...
+..#....#....
+..........#.
+        ...#....
+        .....#..
+        .#......
+        ......#.
+
+10R5L5R10L4R5L5
+";
+
+    #[test]
+    fn parses_path() {
+        let notes: Notes = SAMPLE.parse().unwrap();
+        assert_eq!(notes.path.len(), 13);
+        assert_eq!(notes.path[0], Instruction::Forward(10));
+        assert_eq!(notes.path[1], Instruction::Turn(Turn::Clockwise));
+        assert_eq!(notes.path[3], Instruction::Turn(Turn::Counterclockwise));
+        assert_eq!(notes.board.rows.len(), 12);
+        assert_eq!(notes.board.width, 16);
+    }
+
+    #[test]
+    fn part1_works() {
+        let notes: Notes = SAMPLE.parse().unwrap();
+        assert_eq!(6032, notes.walk(&FlatWrapping::new(&notes.board)));
+    }
+
+    #[test]
+    fn part2_works() {
+        let notes: Notes = SAMPLE.parse().unwrap();
+        let cube = CubeWrapping::new(&notes.board).unwrap();
+        assert_eq!(4, cube.size);
+        assert_eq!(5031, notes.walk(&cube));
+    }
+
+    #[test]
+    fn cube_wrapping_works_for_other_nets() {
+        // The same shape as most real inputs; every edge walked off and back on again should return to where it started.
+        let raw = [
+            "   ......",
+            "   ......",
+            "   ......",
+            "   ...",
+            "   ...",
+            "   ...",
+            "......",
+            "......",
+            "......",
+            "...",
+            "...",
+            "...",
+            "",
+            "1",
+        ]
+        .join("\n");
+
+        let notes: Notes = raw.parse().unwrap();
+        let cube = CubeWrapping::new(&notes.board).unwrap();
+        assert_eq!(3, cube.size);
+        // Walking up off the top face lands on the left edge of the bottom face.
+        assert_eq!(
+            ((0, 9), Facing::Right),
+            notes.step(&cube, (3, 0), Facing::Up)
+        );
+
+        for (y, row) in notes.board.rows.iter().enumerate() {
+            for (x, _) in row.iter().enumerate().filter(|(_, t)| **t != Tile::Void) {
+                for facing in Facing::ALL {
+                    let (next, next_facing) = notes.step(&cube, (x, y), facing);
+                    let back = next_facing.turn(Turn::Clockwise).turn(Turn::Clockwise);
+                    let (home, home_facing) = notes.step(&cube, next, back);
+                    assert_eq!((x, y), home);
+                    assert_eq!(
+                        facing.turn(Turn::Clockwise).turn(Turn::Clockwise),
+                        home_facing
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_boards_that_do_not_fold_into_a_cube() {
+        let raw = ["......"; 4].join("\n") + "\n\n1";
+        let notes: Notes = raw.parse().unwrap();
+        assert!(CubeWrapping::new(&notes.board).is_err());
+    }
 }
@@ -1,7 +1,334 @@
-// https://adventofcode.com/2022/day/2
-use aoc2022::{Part, Result};
-use std::io::Read;
+// https://adventofcode.com/2022/day/23
+use aoc2022::{Error, Options, Part, Result};
+use std::{
+    fmt,
+    io::{self, Read, Write},
+    str::FromStr,
+};
 
-pub(crate) fn execute<R: Read>(_part: &Part, mut _reader: R) -> Result<()> {
-    unimplemented!();
+pub(crate) fn execute<R: Read>(part: &Part, options: &Options, mut reader: R) -> Result<()> {
+    let mut raw = String::new();
+    reader.read_to_string(&mut raw)?;
+
+    let mut grove: Grove = raw.parse()?;
+    let mut frames = options.frames.then(io::stderr);
+    let mut dump = |grove: &Grove| -> Result<()> {
+        if let Some(out) = frames.as_mut() {
+            writeln!(out, "== End of Round {} ==\n{grove}", grove.round)?;
+        }
+
+        Ok(())
+    };
+
+    let answer = match part {
+        Part::Part1 => {
+            for _ in 0..10 {
+                grove.step();
+                dump(&grove)?;
+            }
+
+            grove.empty_ground()
+        }
+        Part::Part2 => loop {
+            let moved = grove.step();
+            dump(&grove)?;
+            if moved == 0 {
+                break grove.round;
+            }
+        },
+    };
+
+    println!("{answer}");
+    Ok(())
+}
+
+#[derive(Debug, Copy, Clone)]
+enum Direction {
+    North,
+    South,
+    West,
+    East,
+}
+
+impl Direction {
+    /// The order in which elves consider directions on the first round. Each round after that starts one further along.
+    const ORDER: [Direction; 4] = [
+        Direction::North,
+        Direction::South,
+        Direction::West,
+        Direction::East,
+    ];
+
+    /// The three neighbouring offsets that must be empty to move in this direction; the middle one is the move itself.
+    fn scan(self) -> [(isize, isize); 3] {
+        match self {
+            Direction::North => [(-1, -1), (0, -1), (1, -1)],
+            Direction::South => [(-1, 1), (0, 1), (1, 1)],
+            Direction::West => [(-1, -1), (-1, 0), (-1, 1)],
+            Direction::East => [(1, -1), (1, 0), (1, 1)],
+        }
+    }
+}
+
+const NEIGHBOURS: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+/// The grove is stored as a dense grid which is regrown whenever an elf gets close to the edge, so it can spread out without bound.
+///
+/// A dense grid is far quicker to probe than a set of positions, which matters when running for upwards of a thousand rounds.
+#[derive(Debug)]
+struct Grove {
+    width: usize,
+    height: usize,
+    occupied: Vec<bool>,
+    /// How many elves proposed to move into each cell this round.
+    proposals: Vec<u8>,
+    /// Indices into the grid of every elf.
+    elves: Vec<usize>,
+    round: usize,
+}
+
+impl FromStr for Grove {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let lines: Vec<&str> = s.lines().filter(|line| !line.is_empty()).collect();
+        let height = lines.len();
+        let width = lines.iter().map(|line| line.len()).max().unwrap_or(0);
+
+        let mut positions = Vec::new();
+        for (y, line) in lines.iter().enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                match c {
+                    '#' => positions.push((x, y)),
+                    '.' => {}
+                    c => return Err(Error::InvalidInput(format!("unknown tile '{c}'"))),
+                }
+            }
+        }
+
+        let mut grove = Grove {
+            width: 0,
+            height: 0,
+            occupied: Vec::new(),
+            proposals: Vec::new(),
+            elves: Vec::new(),
+            round: 0,
+        };
+
+        grove.rebuild(width, height, positions.into_iter());
+        Ok(grove)
+    }
+}
+
+impl Grove {
+    /// Lays the given positions out on a fresh grid with a generous margin on every side.
+    fn rebuild(
+        &mut self,
+        width: usize,
+        height: usize,
+        positions: impl Iterator<Item = (usize, usize)>,
+    ) {
+        let margin = (width.max(height) / 2).max(4);
+        self.width = width + 2 * margin;
+        self.height = height + 2 * margin;
+        self.occupied = vec![false; self.width * self.height];
+        self.proposals = vec![0; self.width * self.height];
+        self.elves.clear();
+        for (x, y) in positions {
+            let index = (y + margin) * self.width + x + margin;
+            self.occupied[index] = true;
+            self.elves.push(index);
+        }
+    }
+
+    fn coordinates(&self, index: usize) -> (usize, usize) {
+        (index % self.width, index / self.width)
+    }
+
+    fn offset(&self, index: usize, (dx, dy): (isize, isize)) -> usize {
+        (index as isize + dy * self.width as isize + dx) as usize
+    }
+
+    /// Returns the smallest rectangle containing every elf, as (min x, min y, max x, max y).
+    fn bounds(&self) -> (usize, usize, usize, usize) {
+        self.elves.iter().fold(
+            (usize::MAX, usize::MAX, 0, 0),
+            |(min_x, min_y, max_x, max_y), index| {
+                let (x, y) = self.coordinates(*index);
+                (min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y))
+            },
+        )
+    }
+
+    /// Regrows the grid if any elf is on the outermost ring, so that neighbour lookups never fall off the edge.
+    fn ensure_margin(&mut self) {
+        let (min_x, min_y, max_x, max_y) = self.bounds();
+        if min_x > 0 && min_y > 0 && max_x + 1 < self.width && max_y + 1 < self.height {
+            return;
+        }
+
+        let positions: Vec<(usize, usize)> = self
+            .elves
+            .iter()
+            .map(|index| {
+                let (x, y) = self.coordinates(*index);
+                (x - min_x, y - min_y)
+            })
+            .collect();
+
+        self.rebuild(max_x - min_x + 1, max_y - min_y + 1, positions.into_iter());
+    }
+
+    /// Runs a single round, returning the number of elves that moved.
+    fn step(&mut self) -> usize {
+        if self.elves.is_empty() {
+            self.round += 1;
+            return 0;
+        }
+
+        self.ensure_margin();
+
+        let targets: Vec<Option<usize>> = self.elves.iter().map(|&elf| self.propose(elf)).collect();
+
+        for target in targets.iter().flatten() {
+            self.proposals[*target] = self.proposals[*target].saturating_add(1);
+        }
+
+        let mut moved = 0;
+        for (elf, target) in self.elves.iter_mut().zip(&targets) {
+            let Some(target) = *target else {
+                continue;
+            };
+
+            if self.proposals[target] == 1 {
+                self.occupied[*elf] = false;
+                self.occupied[target] = true;
+                *elf = target;
+                moved += 1;
+            }
+        }
+
+        for target in targets.into_iter().flatten() {
+            self.proposals[target] = 0;
+        }
+
+        self.round += 1;
+        moved
+    }
+
+    /// Returns the cell an elf would like to move into this round, if any.
+    fn propose(&self, elf: usize) -> Option<usize> {
+        let alone = NEIGHBOURS
+            .iter()
+            .all(|offset| !self.occupied[self.offset(elf, *offset)]);
+
+        if alone {
+            return None;
+        }
+
+        (0..Direction::ORDER.len())
+            .map(|n| Direction::ORDER[(self.round + n) % Direction::ORDER.len()].scan())
+            .find(|scan| {
+                scan.iter()
+                    .all(|offset| !self.occupied[self.offset(elf, *offset)])
+            })
+            .map(|scan| self.offset(elf, scan[1]))
+    }
+
+    /// Counts the empty ground tiles within the smallest rectangle containing every elf.
+    fn empty_ground(&self) -> usize {
+        if self.elves.is_empty() {
+            return 0;
+        }
+
+        let (min_x, min_y, max_x, max_y) = self.bounds();
+        (max_x - min_x + 1) * (max_y - min_y + 1) - self.elves.len()
+    }
+}
+
+impl fmt::Display for Grove {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.elves.is_empty() {
+            return Ok(());
+        }
+
+        let (min_x, min_y, max_x, max_y) = self.bounds();
+        for y in min_y..=max_y {
+            for x in min_x..=max_x {
+                let c = if self.occupied[y * self.width + x] {
+                    '#'
+                } else {
+                    '.'
+                };
+                write!(f, "{c}")?;
+            }
+
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Grove;
+
+    const SMALL: &str = "
+.....
+..##.
+..#..
+.....
+..##.
+.....
+";
+
+    const SAMPLE: &str = "
+....#..
+..###.#
+#...#.#
+.#...##
+#.###..
+##.#.##
+.#..#..
+";
+
+    #[test]
+    fn small_example_settles() {
+        let mut grove: Grove = SMALL.parse().unwrap();
+        assert_eq!(3, grove.step());
+        grove.step();
+        grove.step();
+        assert_eq!(0, grove.step());
+        assert_eq!(
+            "..#..\n....#\n#....\n....#\n.....\n..#..\n",
+            grove.to_string()
+        );
+    }
+
+    #[test]
+    fn part1_works() {
+        let mut grove: Grove = SAMPLE.parse().unwrap();
+        for _ in 0..10 {
+            grove.step();
+        }
+
+        assert_eq!(110, grove.empty_ground());
+    }
+
+    #[test]
+    fn part2_works() {
+        let mut grove: Grove = SAMPLE.parse().unwrap();
+        while grove.step() > 0 {}
+        assert_eq!(20, grove.round);
+    }
 }
@@ -1,7 +1,228 @@
-// https://adventofcode.com/2022/day/2
-use aoc2022::{Part, Result};
-use std::io::Read;
+// https://adventofcode.com/2022/day/24
+use aoc2022::{Error, Part, Result};
+use std::{
+    collections::{HashSet, VecDeque},
+    io::Read,
+    str::FromStr,
+};
 
-pub(crate) fn execute<R: Read>(_part: &Part, mut _reader: R) -> Result<()> {
-    unimplemented!();
+pub(crate) fn execute<R: Read>(part: &Part, mut reader: R) -> Result<()> {
+    let mut raw = String::new();
+    reader.read_to_string(&mut raw)?;
+
+    let valley: Valley = raw.parse()?;
+    let minutes = match part {
+        Part::Part1 => valley.cross(0, valley.start, valley.end),
+        Part::Part2 => valley.there_and_back_again(),
+    };
+
+    let minutes =
+        minutes.ok_or_else(|| Error::InvalidInput("no path through the valley".into()))?;
+    println!("{minutes}");
+    Ok(())
+}
+
+/// (x, y) within the valley, not counting the surrounding wall. The entrance and exit sit at y = -1 and y = height respectively.
+type Position = (i64, i64);
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+fn lcm(a: usize, b: usize) -> usize {
+    a / gcd(a, b) * b
+}
+
+/// The valley, with every blizzard recorded where it started.
+///
+/// Blizzards only ever travel in a straight line and wrap around, so rather than simulating them we can work backwards from a position and time to the cell a blizzard would have to have started in to be there now.
+/// That means the whole valley repeats every lcm(width, height) minutes.
+#[derive(Debug)]
+struct Valley {
+    width: usize,
+    height: usize,
+    /// One grid per direction, indexed by y * width + x, marking where blizzards travelling that way started.
+    up: Vec<bool>,
+    down: Vec<bool>,
+    left: Vec<bool>,
+    right: Vec<bool>,
+    start: Position,
+    end: Position,
+}
+
+impl FromStr for Valley {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let lines: Vec<&str> = s.lines().filter(|line| !line.is_empty()).collect();
+        // There must be at least one row and one column inside the walls, or the blizzards would never repeat.
+        if lines.len() < 3 || lines[0].len() < 3 {
+            return Err(Error::InvalidInput("valley is too small".into()));
+        }
+
+        let opening = |line: &str| {
+            line.find('.')
+                .map(|x| x as i64 - 1)
+                .ok_or_else(|| Error::InvalidInput(format!("no opening in wall '{line}'")))
+        };
+
+        let width = lines[0].len() - 2;
+        let height = lines.len() - 2;
+        let start = (opening(lines[0])?, -1);
+        let end = (opening(lines[lines.len() - 1])?, height as i64);
+
+        let mut valley = Valley {
+            width,
+            height,
+            up: vec![false; width * height],
+            down: vec![false; width * height],
+            left: vec![false; width * height],
+            right: vec![false; width * height],
+            start,
+            end,
+        };
+
+        for (y, line) in lines[1..=height].iter().enumerate() {
+            let interior = line
+                .get(1..line.len().saturating_sub(1))
+                .filter(|interior| interior.len() == width)
+                .ok_or_else(|| Error::InvalidInput(format!("row {} is ragged", y + 2)))?;
+
+            for (x, c) in interior.chars().enumerate() {
+                let grid = match c {
+                    '.' => continue,
+                    '^' => &mut valley.up,
+                    'v' => &mut valley.down,
+                    '<' => &mut valley.left,
+                    '>' => &mut valley.right,
+                    c => return Err(Error::InvalidInput(format!("unknown tile '{c}'"))),
+                };
+
+                grid[y * width + x] = true;
+            }
+        }
+
+        Ok(valley)
+    }
+}
+
+impl Valley {
+    fn period(&self) -> usize {
+        lcm(self.width, self.height)
+    }
+
+    /// Returns true if the given position can be stood on at minute t.
+    fn is_clear(&self, (x, y): Position, t: usize) -> bool {
+        if (x, y) == self.start || (x, y) == self.end {
+            return true;
+        }
+
+        if x < 0 || y < 0 || x >= self.width as i64 || y >= self.height as i64 {
+            return false;
+        }
+
+        let (x, y) = (x as usize, y as usize);
+        let (w, h) = (self.width, self.height);
+        let (tx, ty) = (t % w, t % h);
+        let index = |x: usize, y: usize| y * w + x;
+
+        !(self.right[index((x + w - tx) % w, y)]
+            || self.left[index((x + tx) % w, y)]
+            || self.down[index(x, (y + h - ty) % h)]
+            || self.up[index(x, (y + ty) % h)])
+    }
+
+    /// Returns the earliest minute, at or after `departure`, at which we can reach `to` having set off from `from`.
+    ///
+    /// The search is a breadth-first search over (position, time), where time is folded into the valley's period; once we've stood somewhere at the same point in the cycle there's nothing new to learn by doing it again.
+    fn cross(&self, departure: usize, from: Position, to: Position) -> Option<usize> {
+        let period = self.period();
+        let mut seen = HashSet::new();
+        let mut queue = VecDeque::from([(from, departure)]);
+        seen.insert((from, departure % period));
+
+        while let Some(((x, y), t)) = queue.pop_front() {
+            if (x, y) == to {
+                return Some(t);
+            }
+
+            for next in [(x, y), (x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)] {
+                if self.is_clear(next, t + 1) && seen.insert((next, (t + 1) % period)) {
+                    queue.push_back((next, t + 1));
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Crosses the valley, goes back for the snacks, then crosses it again.
+    fn there_and_back_again(&self) -> Option<usize> {
+        let there = self.cross(0, self.start, self.end)?;
+        let back = self.cross(there, self.end, self.start)?;
+        self.cross(back, self.start, self.end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Valley;
+
+    const SAMPLE: &str = "
+#.######
+#>>.<^<#
+#.<..<<#
+#>v.><>#
+#<^v^^>#
+######.#
+";
+
+    #[test]
+    fn computes_blizzards_from_time() {
+        let simple: Valley = "
+#.#####
+#.....#
+#>....#
+#.....#
+#.....#
+#...v.#
+#####.#
+"
+        .parse()
+        .unwrap();
+
+        assert_eq!(5, simple.width);
+        assert_eq!(5, simple.height);
+        assert!(!simple.is_clear((0, 1), 0));
+        assert!(!simple.is_clear((3, 1), 3));
+        assert!(!simple.is_clear((3, 0), 1));
+        assert!(simple.is_clear((0, 1), 1));
+        // Both blizzards are back where they started after a full period.
+        assert!(!simple.is_clear((0, 1), 5));
+        assert!(!simple.is_clear((3, 4), 5));
+    }
+
+    #[test]
+    fn rejects_empty_valleys() {
+        for raw in ["#.\n#.\n.#", "\n.\n.\n.\n", "#.#\n.#"] {
+            assert!(raw.parse::<Valley>().is_err(), "{raw:?}");
+        }
+    }
+
+    #[test]
+    fn part1_works() {
+        let valley: Valley = SAMPLE.parse().unwrap();
+        assert_eq!(12, valley.period());
+        assert_eq!(Some(18), valley.cross(0, valley.start, valley.end));
+    }
+
+    #[test]
+    fn part2_works() {
+        let valley: Valley = SAMPLE.parse().unwrap();
+        assert_eq!(Some(54), valley.there_and_back_again());
+    }
 }
@@ -1,7 +1,260 @@
-// https://adventofcode.com/2022/day/2
-use aoc2022::{Part, Result};
-use std::io::Read;
+// https://adventofcode.com/2022/day/25
+use aoc2022::{Error, Part, Result};
+use std::{
+    fmt,
+    io::{BufRead, BufReader, Read},
+    iter::Sum,
+    str::FromStr,
+};
 
-pub(crate) fn execute<R: Read>(_part: &Part, mut _reader: R) -> Result<()> {
-    unimplemented!();
+pub(crate) fn execute<R: Read>(part: &Part, reader: R) -> Result<()> {
+    match part {
+        Part::Part1 => println!("{}", solve(reader)?),
+        // There is no second puzzle on the last day; the final star is awarded for finishing every other day.
+        Part::Part2 => println!("Merry Christmas!"),
+    };
+
+    Ok(())
+}
+
+fn solve<R: Read>(reader: R) -> Result<Snafu> {
+    let mut numbers = Vec::new();
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        if line.is_empty() {
+            continue;
+        }
+
+        numbers.push(line.parse::<Snafu>()?);
+    }
+
+    Ok(numbers.into_iter().sum())
+}
+
+/// A number written in Special Numeral-Analogue Fuel Units; balanced base 5, where each digit is one of `2`, `1`, `0`, `-` (minus one) or `=` (minus two).
+///
+/// Every i128 has exactly one SNAFU representation, so there's no need for a separate sign.
+#[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Hash)]
+struct Snafu(i128);
+
+#[derive(Debug, PartialEq, Eq)]
+enum SnafuError {
+    Empty,
+    InvalidDigit(char),
+    Overflow,
+}
+
+impl From<SnafuError> for Error {
+    fn from(err: SnafuError) -> Self {
+        let message = match err {
+            SnafuError::Empty => "empty SNAFU number".into(),
+            SnafuError::InvalidDigit(c) => format!("invalid SNAFU digit '{c}'"),
+            SnafuError::Overflow => "SNAFU number is out of range".into(),
+        };
+
+        Error::InvalidInput(message)
+    }
+}
+
+impl From<i128> for Snafu {
+    fn from(n: i128) -> Self {
+        Snafu(n)
+    }
+}
+
+impl From<i64> for Snafu {
+    fn from(n: i64) -> Self {
+        Snafu(n.into())
+    }
+}
+
+impl From<Snafu> for i128 {
+    fn from(n: Snafu) -> Self {
+        n.0
+    }
+}
+
+impl TryFrom<Snafu> for i64 {
+    type Error = SnafuError;
+
+    fn try_from(n: Snafu) -> std::result::Result<Self, Self::Error> {
+        i64::try_from(n.0).map_err(|_| SnafuError::Overflow)
+    }
+}
+
+impl FromStr for Snafu {
+    type Err = SnafuError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(SnafuError::Empty);
+        }
+
+        let mut n: i128 = 0;
+        for c in s.chars() {
+            let digit = match c {
+                '2' => 2,
+                '1' => 1,
+                '0' => 0,
+                '-' => -1,
+                '=' => -2,
+                c => return Err(SnafuError::InvalidDigit(c)),
+            };
+
+            // Near the ends of the range a prefix can overflow even though the full number fits, so wrap here and check afterwards.
+            n = n.wrapping_mul(5).wrapping_add(digit);
+        }
+
+        // Every number has exactly one SNAFU representation; if wrapping changed the value, it will print differently from what we were given.
+        let snafu = Snafu(n);
+        let significant = s.trim_start_matches('0');
+        if !significant.is_empty() && snafu.to_string() != significant {
+            return Err(SnafuError::Overflow);
+        }
+
+        Ok(snafu)
+    }
+}
+
+impl fmt::Display for Snafu {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0 == 0 {
+            return write!(f, "0");
+        }
+
+        let mut digits = Vec::new();
+        let mut n = self.0;
+        while n != 0 {
+            // Working from the euclidean quotient rather than subtracting the digit first avoids overflowing at i128::MIN.
+            let mut quotient = n.div_euclid(5);
+            let digit = match n.rem_euclid(5) {
+                0 => '0',
+                1 => '1',
+                2 => '2',
+                3 => {
+                    quotient += 1;
+                    '='
+                }
+                _ => {
+                    quotient += 1;
+                    '-'
+                }
+            };
+
+            digits.push(digit);
+            n = quotient;
+        }
+
+        let s: String = digits.into_iter().rev().collect();
+        f.pad(&s)
+    }
+}
+
+impl Sum for Snafu {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        Snafu(iter.map(|n| n.0).sum())
+    }
+}
+
+impl<'a> Sum<&'a Snafu> for Snafu {
+    fn sum<I: Iterator<Item = &'a Snafu>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{solve, Snafu, SnafuError};
+    use proptest::prelude::*;
+
+    const SAMPLE: &[u8] = b"
+1=-0-2
+12111
+2=0=
+21
+2=01
+111
+20012
+112
+1=-1=
+1-12
+12
+1=
+122
+";
+
+    #[test]
+    fn converts_examples() {
+        let examples: &[(i128, &str)] = &[
+            (1, "1"),
+            (2, "2"),
+            (3, "1="),
+            (4, "1-"),
+            (5, "10"),
+            (8, "2="),
+            (10, "20"),
+            (15, "1=0"),
+            (20, "1-0"),
+            (2022, "1=11-2"),
+            (12345, "1-0---0"),
+            (314159265, "1121-1110-1=0"),
+        ];
+
+        for (decimal, snafu) in examples {
+            assert_eq!(*snafu, Snafu::from(*decimal).to_string());
+            assert_eq!(Ok(Snafu::from(*decimal)), snafu.parse());
+        }
+    }
+
+    #[test]
+    fn rejects_bad_input() {
+        assert_eq!(Err(SnafuError::Empty), "".parse::<Snafu>());
+        assert_eq!(Err(SnafuError::InvalidDigit('3')), "1=3".parse::<Snafu>());
+        let too_big = Snafu::from(i128::MAX).to_string() + "0";
+        assert_eq!(Err(SnafuError::Overflow), too_big.parse::<Snafu>());
+        assert_eq!(Ok(Snafu::from(2i64)), "002".parse::<Snafu>());
+    }
+
+    #[test]
+    fn handles_extremes() {
+        for n in [i128::MIN, i128::MAX, -1, 0] {
+            assert_eq!(Ok(Snafu::from(n)), Snafu::from(n).to_string().parse());
+        }
+
+        assert_eq!("-", Snafu::from(-1i64).to_string());
+        assert_eq!(Ok(i64::MIN), i64::try_from(Snafu::from(i64::MIN)));
+        assert_eq!(
+            Err(SnafuError::Overflow),
+            i64::try_from(Snafu::from(i64::MAX as i128 + 1))
+        );
+    }
+
+    #[test]
+    fn solve_works() {
+        assert_eq!("2=-1=0", solve(SAMPLE).unwrap().to_string());
+    }
+
+    proptest! {
+        #[test]
+        fn round_trips_i64(n in any::<i64>()) {
+            let s = Snafu::from(n).to_string();
+            prop_assert_eq!(Ok(n), s.parse::<Snafu>().and_then(i64::try_from));
+        }
+
+        #[test]
+        fn round_trips_i128(n in any::<i128>()) {
+            let s = Snafu::from(n).to_string();
+            prop_assert_eq!(Ok(Snafu::from(n)), s.parse::<Snafu>());
+        }
+
+        #[test]
+        fn sum_matches_integer_sum(ns in prop::collection::vec(any::<i64>(), 0..32)) {
+            let expected: i128 = ns.iter().map(|n| *n as i128).sum();
+            let snafus: Vec<Snafu> = ns
+                .iter()
+                .map(|n| Snafu::from(*n).to_string().parse().unwrap())
+                .collect();
+            prop_assert_eq!(Snafu::from(expected), snafus.iter().sum());
+        }
+    }
 }
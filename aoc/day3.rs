@@ -59,77 +59,619 @@
 /// Priorities for these items must still be found to organize the sticker attachment efforts: here, they are 18 (r) for the first group and 52 (Z) for the second group. The sum of these is 70.
 ///
 /// Find the item type that corresponds to the badges of each three-Elf group. What is the sum of the priorities of those item types?
-use aoc2022::{Part, Result};
+use aoc2022::{Error, Mode, Options, Part, Result};
 use itertools::Itertools;
 use std::{
-    collections::HashSet,
+    fmt,
     io::{BufRead, BufReader, Read},
+    ops::{BitAnd, BitOr},
 };
 
-pub(crate) fn execute<R: Read>(part: &Part, reader: R) -> Result<()> {
-    match part {
-        Part::Part1 => println!("{}", solve(reader)),
-        Part::Part2 => println!("{}", solve2_with_index(reader)),
+pub(crate) fn execute<R: Read>(part: &Part, options: &Options, reader: R) -> Result<()> {
+    let group_size = options.group_size.unwrap_or(3);
+    let explain = match options.mode {
+        Some(Mode::Explain) => true,
+        Some(Mode::Regroup) => return print_regrouped(reader, group_size),
+        Some(mode) => return Err(Error::UnsupportedMode(mode)),
+        None => false,
     };
 
+    let mut report = |finding: Finding| {
+        if explain {
+            println!("{finding}");
+        }
+    };
+
+    let sum = match part {
+        Part::Part1 => find_shared_items(reader, &mut report)?,
+        Part::Part2 => find_badges(reader, group_size, &mut report)?,
+    };
+
+    if explain {
+        println!("total: {sum}");
+    } else {
+        println!("{sum}");
+    }
+
     Ok(())
 }
 
-#[derive(Debug)]
-struct ErrControlCharacter;
+/// Everything that can be wrong with a list of rucksacks. Lines are numbered from 1, as are columns.
+#[derive(Debug, PartialEq, Eq)]
+enum RucksackError {
+    InvalidItem {
+        line: usize,
+        column: usize,
+        item: char,
+    },
+    OddLength {
+        line: usize,
+        length: usize,
+    },
+    NoSharedItem {
+        line: usize,
+    },
+    SeveralSharedItems {
+        line: usize,
+        items: ItemSet,
+    },
+    NoBadge {
+        lines: (usize, usize),
+    },
+    SeveralBadges {
+        lines: (usize, usize),
+        items: ItemSet,
+    },
+    IncompleteGroup {
+        lines: (usize, usize),
+    },
+}
+
+impl RucksackError {
+    fn line(&self) -> usize {
+        match self {
+            Self::InvalidItem { line, .. }
+            | Self::OddLength { line, .. }
+            | Self::NoSharedItem { line }
+            | Self::SeveralSharedItems { line, .. } => *line,
+            Self::NoBadge { lines, .. }
+            | Self::SeveralBadges { lines, .. }
+            | Self::IncompleteGroup { lines } => lines.0,
+        }
+    }
+
+    fn message(&self) -> String {
+        match self {
+            Self::InvalidItem { column, item, .. } => {
+                format!(
+                    "column {column}: '{}' is not an item type",
+                    item.escape_debug()
+                )
+            }
+            Self::OddLength { length, .. } => {
+                format!("{length} items can't be split evenly between two compartments")
+            }
+            Self::NoSharedItem { .. } => "no item type is in both compartments".into(),
+            Self::SeveralSharedItems { items, .. } => {
+                format!("item types {items} are all in both compartments")
+            }
+            Self::NoBadge {
+                lines: (first, last),
+            } => {
+                format!("no item type is common to lines {first} to {last}")
+            }
+            Self::SeveralBadges {
+                lines: (first, last),
+                items,
+            } => format!("item types {items} are all common to lines {first} to {last}"),
+            Self::IncompleteGroup {
+                lines: (first, last),
+            } => format!("lines {first} to {last} don't make up a full group"),
+        }
+    }
+}
 
-fn priority(ch: char) -> std::result::Result<u32, ErrControlCharacter> {
-    match ch as u8 {
-        c if c > 96 => Ok(c as u32 - 96),
+impl fmt::Display for RucksackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line(), self.message())
+    }
+}
+
+impl From<RucksackError> for aoc2022::Error {
+    fn from(err: RucksackError) -> Self {
+        aoc2022::Error::Parse {
+            line: err.line(),
+            message: err.message(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct InvalidItemError(char);
+
+const fn priority(ch: char) -> std::result::Result<u32, InvalidItemError> {
+    match ch {
+        'a'..='z' => Ok(ch as u32 - 'a' as u32 + 1),
         // Upper case letters need to have 27 added, because the minimum priority they can be is 27
-        c if c < 91 => Ok(c as u32 - 65 + 27),
-        _ => Err(ErrControlCharacter {}),
+        'A'..='Z' => Ok(ch as u32 - 'A' as u32 + 27),
+        _ => Err(InvalidItemError(ch)),
     }
 }
 
-fn solve<R: Read>(reader: R) -> u32 {
-    BufReader::new(reader)
-        .lines()
-        .filter_ok(|line| !line.is_empty())
-        .map_ok(|line| line.chars().collect::<Vec<char>>())
-        .fold_ok(0u32, |acc, chars| {
-            let mid = chars.len() / 2;
-            let halves: (HashSet<char>, HashSet<char>) = (
-                HashSet::from_iter(chars.iter().copied().take(mid)),
-                HashSet::from_iter(chars.iter().copied().skip(mid)),
-            );
+/// The item type with the given priority; the inverse of [priority].
+fn item(priority: u32) -> char {
+    match priority {
+        1..=26 => (b'a' + priority as u8 - 1) as char,
+        _ => (b'A' + priority as u8 - 27) as char,
+    }
+}
 
-            let shared = halves.0.intersection(&halves.1).next();
-            acc + priority(*shared.unwrap()).unwrap()
-        })
-        .unwrap()
-}
-
-fn solve2_with_index<R: Read>(reader: R) -> u32 {
-    // Another way to solve the Part 2 problem is to build an index of all characters used in each group, and count which char shows up exactly 3 times.
-    BufReader::new(reader)
-        .lines()
-        .filter_ok(|line| !line.is_empty())
-        .chunks(3)
-        .into_iter()
-        .fold(0, |acc, group| {
-            // This will panic if there's a problem reading lines, or if any group contains more than exactly one character that is shared.
-            acc + group
-                .map(|line| HashSet::from_iter(line.unwrap().chars()))
-                .fold(HashSet::new(), |prev, next| {
-                    prev.intersection(&next).copied().collect()
-                })
-                .into_iter()
+/// A set of item types, stored as one bit per type.
+///
+/// There are only 52 item types, so a set fits in a u64; bit n is set if the item with priority n + 1 is present.
+/// That makes intersection a single AND, and finding the priority of an item a trailing-zero count.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
+struct ItemSet(u64);
+
+impl ItemSet {
+    /// Every item type.
+    const ALL: ItemSet = ItemSet((1 << 52) - 1);
+
+    /// Set on lookups of anything that isn't an item, so validity can be checked once per set rather than once per item.
+    const INVALID: u64 = 1 << 63;
+
+    /// The bit for every byte, so that building a set is a branch-free run of lookups.
+    const BITS: [u64; 256] = {
+        let mut bits = [ItemSet::INVALID; 256];
+        let mut byte = 0;
+        while byte < bits.len() {
+            if let Ok(priority) = priority(byte as u8 as char) {
+                bits[byte] = 1 << (priority - 1);
+            }
+
+            byte += 1;
+        }
+
+        bits
+    };
+
+    /// Builds the set of items on (part of) a line, returning the offset of the first thing that isn't an item if there is one.
+    fn from_items(items: &[u8]) -> std::result::Result<Self, usize> {
+        let bits = items
+            .iter()
+            .fold(0, |bits, item| bits | ItemSet::BITS[*item as usize]);
+
+        if bits & ItemSet::INVALID == 0 {
+            return Ok(ItemSet(bits));
+        }
+
+        Err(items
+            .iter()
+            .position(|item| ItemSet::BITS[*item as usize] == ItemSet::INVALID)
+            .unwrap_or_default())
+    }
+
+    fn len(self) -> u32 {
+        self.0.count_ones()
+    }
+
+    /// Returns the priority of the only item in the set, or the set itself if it doesn't hold exactly one item.
+    fn only(self) -> std::result::Result<u32, ItemSet> {
+        match self.len() {
+            1 => Ok(self.0.trailing_zeros() + 1),
+            _ => Err(self),
+        }
+    }
+
+    fn priorities(self) -> impl Iterator<Item = u32> {
+        (1..=52).filter(move |priority| self.0 & (1 << (priority - 1)) != 0)
+    }
+}
+
+impl BitAnd for ItemSet {
+    type Output = ItemSet;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        ItemSet(self.0 & rhs.0)
+    }
+}
+
+impl BitOr for ItemSet {
+    type Output = ItemSet;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        ItemSet(self.0 | rhs.0)
+    }
+}
+
+impl fmt::Display for ItemSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let items: Vec<String> = self.priorities().map(|p| item(p).to_string()).collect();
+        f.pad(&items.join(", "))
+    }
+}
+
+/// A single rucksack, split into its two compartments.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+struct Rucksack {
+    line: usize,
+    left: ItemSet,
+    right: ItemSet,
+}
+
+impl Rucksack {
+    fn parse(line: usize, items: &[u8]) -> std::result::Result<Self, RucksackError> {
+        let invalid = |offset: usize| {
+            // Report the whole character rather than a single byte of it if the line isn't ASCII.
+            let item = String::from_utf8_lossy(&items[offset..])
+                .chars()
                 .next()
-                .map(priority)
-                .unwrap()
-                .unwrap()
+                .unwrap_or_default();
+
+            RucksackError::InvalidItem {
+                line,
+                column: offset + 1,
+                item,
+            }
+        };
+
+        let half = items.len() / 2;
+        let left = ItemSet::from_items(&items[..half]).map_err(invalid)?;
+        let right = ItemSet::from_items(&items[half..]).map_err(|offset| invalid(half + offset))?;
+        if !items.len().is_multiple_of(2) {
+            return Err(RucksackError::OddLength {
+                line,
+                length: items.len(),
+            });
+        }
+
+        Ok(Rucksack { line, left, right })
+    }
+
+    fn items(&self) -> ItemSet {
+        self.left | self.right
+    }
+
+    /// Returns the priority of the one item type that was packed into both compartments.
+    fn shared(&self) -> std::result::Result<u32, RucksackError> {
+        (self.left & self.right)
+            .only()
+            .map_err(|items| match items.len() {
+                0 => RucksackError::NoSharedItem { line: self.line },
+                _ => RucksackError::SeveralSharedItems {
+                    line: self.line,
+                    items,
+                },
+            })
+    }
+}
+
+/// Returns the priority of the badge, the one item type carried by every rucksack in the group.
+fn badge(group: &[Rucksack]) -> std::result::Result<u32, RucksackError> {
+    let lines = (
+        group.first().map(|r| r.line).unwrap_or_default(),
+        group.last().map(|r| r.line).unwrap_or_default(),
+    );
+
+    group
+        .iter()
+        .map(Rucksack::items)
+        .fold(ItemSet::ALL, ItemSet::bitand)
+        .only()
+        .map_err(|items| match items.len() {
+            0 => RucksackError::NoBadge { lines },
+            _ => RucksackError::SeveralBadges { lines, items },
         })
 }
 
+/// Calls `f` with every non-empty line of the reader and its line number, reusing a single buffer rather than allocating a String per line.
+fn for_each_line<R, F>(reader: R, mut f: F) -> Result<()>
+where
+    R: Read,
+    F: FnMut(usize, &[u8]) -> Result<()>,
+{
+    let mut reader = BufReader::new(reader);
+    let mut line = Vec::new();
+    let mut line_number = 0;
+    while reader.read_until(b'\n', &mut line)? > 0 {
+        line_number += 1;
+        let trimmed = line.strip_suffix(b"\n").unwrap_or(&line);
+        let trimmed = trimmed.strip_suffix(b"\r").unwrap_or(trimmed);
+        if !trimmed.is_empty() {
+            f(line_number, trimmed)?;
+        }
+
+        line.clear();
+    }
+
+    Ok(())
+}
+
+/// The item type shared by a rucksack's compartments or a group's rucksacks, and the lines it was found on.
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct Finding {
+    lines: Vec<usize>,
+    priority: u32,
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let consecutive = self.lines.windows(2).all(|pair| pair[0] + 1 == pair[1]);
+        match self.lines.as_slice() {
+            [line] => write!(f, "line {line}: ")?,
+            [first, .., last] if consecutive => write!(f, "lines {first}-{last}: ")?,
+            lines => write!(f, "lines {}: ", lines.iter().join(", "))?,
+        }
+
+        write!(f, "{} (priority {})", item(self.priority), self.priority)
+    }
+}
+
+/// Finds the item type in both compartments of every rucksack, passing each to `report`, and returns the sum of their priorities.
+fn find_shared_items<R, F>(reader: R, mut report: F) -> Result<u32>
+where
+    R: Read,
+    F: FnMut(Finding),
+{
+    let mut sum = 0;
+    for_each_line(reader, |line, items| {
+        let priority = Rucksack::parse(line, items)?.shared()?;
+        report(Finding {
+            lines: vec![line],
+            priority,
+        });
+
+        sum += priority;
+        Ok(())
+    })?;
+
+    Ok(sum)
+}
+
+/// Finds the badge of every group of `group_size` consecutive rucksacks, passing each to `report`, and returns the sum of their priorities.
+fn find_badges<R, F>(reader: R, group_size: usize, mut report: F) -> Result<u32>
+where
+    R: Read,
+    F: FnMut(Finding),
+{
+    if group_size == 0 {
+        return Err(Error::InvalidInput(
+            "--group-size must be at least 1".into(),
+        ));
+    }
+
+    let mut sum = 0;
+    let mut group = Vec::with_capacity(group_size);
+    for_each_line(reader, |line, items| {
+        group.push(Rucksack::parse(line, items)?);
+        if group.len() == group_size {
+            let priority = badge(&group)?;
+            report(Finding {
+                lines: group.iter().map(|rucksack| rucksack.line).collect(),
+                priority,
+            });
+
+            sum += priority;
+            group.clear();
+        }
+
+        Ok(())
+    })?;
+
+    if let (Some(first), Some(last)) = (group.first(), group.last()) {
+        return Err(RucksackError::IncompleteGroup {
+            lines: (first.line, last.line),
+        }
+        .into());
+    }
+
+    Ok(sum)
+}
+
+fn parse_rucksacks<R: Read>(reader: R) -> Result<Vec<Rucksack>> {
+    let mut rucksacks = Vec::new();
+    for_each_line(reader, |line, items| {
+        rucksacks.push(Rucksack::parse(line, items)?);
+        Ok(())
+    })?;
+
+    Ok(rucksacks)
+}
+
+/// Splits shuffled rucksacks back into groups of `group_size`, where each group has exactly one item type in common; its badge.
+///
+/// This is an exact cover problem, so in the worst case there's nothing better than trying every grouping.
+/// Every possible group is listed up front, and the search always branches on the rucksack with the fewest groups left that it could join.
+/// That finds a dead end as soon as some rucksack has nowhere left to go, which in practice finds an answer, or rules one out, very quickly.
+struct Regrouper {
+    /// Every group of rucksacks with exactly one item type in common, as sorted indices into the rucksacks.
+    candidates: Vec<Vec<usize>>,
+    /// The indices of every candidate each rucksack could be part of.
+    joinable: Vec<Vec<usize>>,
+    placed: Vec<bool>,
+    groups: Vec<usize>,
+}
+
+impl Regrouper {
+    /// Returns the groups as indices into `rucksacks`, or None if there is no way to group them.
+    fn regroup(rucksacks: &[Rucksack], group_size: usize) -> Option<Vec<Vec<usize>>> {
+        if group_size == 0 || !rucksacks.len().is_multiple_of(group_size) {
+            return None;
+        }
+
+        let mut candidates = Vec::new();
+        let items: Vec<ItemSet> = rucksacks.iter().map(Rucksack::items).collect();
+        let mut group = Vec::with_capacity(group_size);
+        for first in 0..items.len() {
+            group.push(first);
+            Regrouper::list_candidates(
+                &items,
+                group_size,
+                &mut group,
+                items[first],
+                &mut candidates,
+            );
+            group.pop();
+        }
+
+        let mut joinable = vec![Vec::new(); rucksacks.len()];
+        for (index, candidate) in candidates.iter().enumerate() {
+            for member in candidate {
+                joinable[*member].push(index);
+            }
+        }
+
+        let mut regrouper = Regrouper {
+            candidates,
+            joinable,
+            placed: vec![false; rucksacks.len()],
+            groups: Vec::with_capacity(rucksacks.len() / group_size),
+        };
+
+        if !regrouper.search() {
+            return None;
+        }
+
+        let groups = regrouper
+            .groups
+            .iter()
+            .map(|index| regrouper.candidates[*index].clone())
+            .collect();
+
+        Some(groups)
+    }
+
+    /// Adds every way of completing `group` from the rucksacks after its last member to `candidates`, where `common` is what its members so far have in common.
+    fn list_candidates(
+        items: &[ItemSet],
+        group_size: usize,
+        group: &mut Vec<usize>,
+        common: ItemSet,
+        candidates: &mut Vec<Vec<usize>>,
+    ) {
+        if group.len() == group_size {
+            if common.len() == 1 {
+                candidates.push(group.clone());
+            }
+
+            return;
+        }
+
+        let last = group[group.len() - 1];
+        for next in last + 1..items.len() {
+            // Adding more rucksacks can only shrink what the group has in common, so once that's nothing there's no point going on.
+            let common = common & items[next];
+            if common.len() > 0 {
+                group.push(next);
+                Regrouper::list_candidates(items, group_size, group, common, candidates);
+                group.pop();
+            }
+        }
+    }
+
+    fn is_available(&self, candidate: usize) -> bool {
+        self.candidates[candidate]
+            .iter()
+            .all(|member| !self.placed[*member])
+    }
+
+    /// Returns the rucksack that isn't in a group yet with the fewest candidates it could still join, or None if every rucksack has been placed.
+    fn most_constrained(&self) -> Option<(usize, usize)> {
+        let mut best: Option<(usize, usize)> = None;
+        for (rucksack, joinable) in self.joinable.iter().enumerate() {
+            if self.placed[rucksack] {
+                continue;
+            }
+
+            let limit = best.map_or(usize::MAX, |(_, count)| count);
+            let count = joinable
+                .iter()
+                .filter(|candidate| self.is_available(**candidate))
+                .take(limit)
+                .count();
+
+            if count < limit {
+                best = Some((rucksack, count));
+                if count == 0 {
+                    break;
+                }
+            }
+        }
+
+        best
+    }
+
+    /// Returns true once every rucksack is in a group.
+    fn search(&mut self) -> bool {
+        let Some((rucksack, count)) = self.most_constrained() else {
+            return true;
+        };
+
+        if count == 0 {
+            return false;
+        }
+
+        for index in 0..self.joinable[rucksack].len() {
+            let candidate = self.joinable[rucksack][index];
+            if !self.is_available(candidate) {
+                continue;
+            }
+
+            self.place(candidate, true);
+            self.groups.push(candidate);
+            if self.search() {
+                return true;
+            }
+
+            self.groups.pop();
+            self.place(candidate, false);
+        }
+
+        false
+    }
+
+    fn place(&mut self, candidate: usize, placed: bool) {
+        for member in &self.candidates[candidate] {
+            self.placed[*member] = placed;
+        }
+    }
+}
+
+fn print_regrouped<R: Read>(reader: R, group_size: usize) -> Result<()> {
+    if group_size == 0 {
+        return Err(Error::InvalidInput(
+            "--group-size must be at least 1".into(),
+        ));
+    }
+
+    let rucksacks = parse_rucksacks(reader)?;
+    let Some(groups) = Regrouper::regroup(&rucksacks, group_size) else {
+        println!("no way to split the rucksacks into groups of {group_size} with one badge each");
+        return Ok(());
+    };
+
+    let mut sum = 0;
+    for group in groups {
+        let group: Vec<Rucksack> = group.into_iter().map(|index| rucksacks[index]).collect();
+        let finding = Finding {
+            lines: group.iter().map(|rucksack| rucksack.line).collect(),
+            priority: badge(&group)?,
+        };
+
+        println!("{finding}");
+        sum += finding.priority;
+    }
+
+    println!("total: {sum}");
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{solve, solve2_with_index};
+    use super::{
+        badge, find_badges, find_shared_items, parse_rucksacks, priority, Finding,
+        InvalidItemError, ItemSet, Regrouper, Rucksack, RucksackError,
+    };
 
     const SAMPLE_DATA: &[u8] = b"
 vJrwpWtwJgWrhcsFMMfFFhFp
@@ -141,11 +683,191 @@ CrZsJsPPZsGzwwsLwLmpwMDw";
 
     #[test]
     fn solve_gives_correct_answer_for_sample_data() {
-        assert_eq!(157, solve(SAMPLE_DATA));
+        assert_eq!(157, find_shared_items(SAMPLE_DATA, |_| {}).unwrap());
     }
 
     #[test]
     fn solve2_with_index_gives_correct_answer_for_sample_data() {
-        assert_eq!(70, solve2_with_index(SAMPLE_DATA));
+        assert_eq!(70, find_badges(SAMPLE_DATA, 3, |_| {}).unwrap());
+    }
+
+    #[test]
+    fn item_sets_intersect_by_priority() {
+        let a = ItemSet::from_items(b"vJrwpWtwJgWr").unwrap();
+        let b = ItemSet::from_items(b"hcsFMMfFFhFp").unwrap();
+        assert_eq!(8, a.len());
+        assert_eq!(Ok(16), (a & b).only());
+        assert_eq!(Err(ItemSet::default()), ItemSet::default().only());
+        assert_eq!(52, ItemSet::ALL.len());
+        assert_eq!("g, p, r, t, v, w, J, W", a.to_string());
+
+        for item in ('a'..='z').chain('A'..='Z') {
+            let set = ItemSet::from_items(&[item as u8]).unwrap();
+            assert_eq!(priority(item), Ok(set.only().unwrap()));
+        }
+    }
+
+    #[test]
+    fn priority_rejects_anything_but_letters() {
+        assert_eq!(Ok(1), priority('a'));
+        assert_eq!(Ok(52), priority('Z'));
+        for ch in ['0', '9', '@', '[', '`', '{', ' ', '\0', 'é'] {
+            assert_eq!(Err(InvalidItemError(ch)), priority(ch));
+        }
+    }
+
+    #[test]
+    fn reports_bad_rucksacks() {
+        assert_eq!(
+            Err(RucksackError::InvalidItem {
+                line: 4,
+                column: 6,
+                item: '1'
+            }),
+            Rucksack::parse(4, b"abcdA1")
+        );
+        assert_eq!(
+            Err(RucksackError::InvalidItem {
+                line: 1,
+                column: 2,
+                item: 'é'
+            }),
+            Rucksack::parse(1, "aébc".as_bytes())
+        );
+        assert_eq!(
+            Err(RucksackError::OddLength { line: 2, length: 3 }),
+            Rucksack::parse(2, b"aba")
+        );
+        assert_eq!(
+            Err(RucksackError::NoSharedItem { line: 3 }),
+            Rucksack::parse(3, b"abcd").unwrap().shared()
+        );
+        assert_eq!(
+            Err(RucksackError::SeveralSharedItems {
+                line: 5,
+                items: ItemSet::from_items(b"aB").unwrap()
+            }),
+            Rucksack::parse(5, b"aBcaBd").unwrap().shared()
+        );
+    }
+
+    #[test]
+    fn reports_bad_groups() {
+        let group = |lines: &[&[u8]]| -> Vec<Rucksack> {
+            lines
+                .iter()
+                .enumerate()
+                .map(|(n, items)| Rucksack::parse(n + 1, items).unwrap())
+                .collect()
+        };
+
+        assert_eq!(
+            Err(RucksackError::NoBadge { lines: (1, 3) }),
+            badge(&group(&[b"abab", b"cdcd", b"abcd"]))
+        );
+        assert_eq!(
+            Err(RucksackError::SeveralBadges {
+                lines: (1, 3),
+                items: ItemSet::from_items(b"ab").unwrap()
+            }),
+            badge(&group(&[b"abab", b"abcd", b"abcd"]))
+        );
+
+        let err = find_badges(&SAMPLE_DATA[..SAMPLE_DATA.len() - 25], 3, |_| {}).unwrap_err();
+        assert_eq!(
+            "line 5: lines 5 to 6 don't make up a full group",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn errors_carry_line_numbers() {
+        let err = find_shared_items(
+            &b"\nvJrwpWtwJgWrhcsFMMfFFhFp\n\njqHRNq3jqzjGDLGLrsFMfFZSrLrFZsSL\n"[..],
+            |_| {},
+        )
+        .unwrap_err();
+        assert_eq!("line 4: column 7: '3' is not an item type", err.to_string());
+    }
+
+    #[test]
+    fn explains_each_rucksack() {
+        let mut findings = Vec::new();
+        find_shared_items(SAMPLE_DATA, |finding| findings.push(finding.to_string())).unwrap();
+        assert_eq!(
+            vec![
+                "line 2: p (priority 16)",
+                "line 3: L (priority 38)",
+                "line 4: P (priority 42)",
+                "line 5: v (priority 22)",
+                "line 6: t (priority 20)",
+                "line 7: s (priority 19)",
+            ],
+            findings
+        );
+    }
+
+    #[test]
+    fn groups_can_be_any_size() {
+        let mut findings = Vec::new();
+        let sum = find_badges(SAMPLE_DATA, 3, |finding| findings.push(finding)).unwrap();
+        assert_eq!(70, sum);
+        assert_eq!(
+            vec![
+                Finding {
+                    lines: vec![2, 3, 4],
+                    priority: 18
+                },
+                Finding {
+                    lines: vec![5, 6, 7],
+                    priority: 52
+                }
+            ],
+            findings
+        );
+
+        let pairs = b"abcd\naxef\nxyzB\nBwvu\n";
+        assert_eq!(1 + 28, find_badges(&pairs[..], 2, |_| {}).unwrap());
+        assert!(find_badges(&pairs[..], 0, |_| {}).is_err());
+    }
+
+    #[test]
+    fn regroups_shuffled_rucksacks() {
+        let shuffled = b"
+CrZsJsPPZsGzwwsLwLmpwMDw
+vJrwpWtwJgWrhcsFMMfFFhFp
+ttgJtRGJQctTZtZT
+jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+PmmdzqPrVvPwwTWBwg";
+
+        let rucksacks = parse_rucksacks(&shuffled[..]).unwrap();
+        let groups = Regrouper::regroup(&rucksacks, 3).unwrap();
+        let mut placed: Vec<usize> = groups.iter().flatten().copied().collect();
+        placed.sort();
+        assert_eq!(vec![0, 1, 2, 3, 4, 5], placed);
+
+        // The sample can be grouped more than one way, so just check that this is one of them.
+        for group in &groups {
+            let group: Vec<Rucksack> = group.iter().map(|index| rucksacks[*index]).collect();
+            assert!(badge(&group).is_ok());
+        }
+    }
+
+    #[test]
+    fn regrouping_backtracks() {
+        // Grouping the first rucksack with the next two that share a single item strands the other three, which have nothing in common.
+        let rucksacks = parse_rucksacks(&b"axax\nazaz\nbzbz\nbxbx\nayay\nbyby\n"[..]).unwrap();
+        assert_eq!(
+            Some(vec![vec![0, 1, 4], vec![2, 3, 5]]),
+            Regrouper::regroup(&rucksacks, 3)
+        );
+    }
+
+    #[test]
+    fn regrouping_proves_there_is_no_answer() {
+        let rucksacks = parse_rucksacks(&b"abab\nacac\nadad\nbcbc\nbdbd\ncdcd\n"[..]).unwrap();
+        assert_eq!(None, Regrouper::regroup(&rucksacks, 3));
+        assert_eq!(None, Regrouper::regroup(&rucksacks, 4));
     }
 }
@@ -42,69 +42,441 @@
 /// Some of the pairs have noticed that one of their assignments fully contains the other. For example, 2-8 fully contains 3-7, and 6-6 is fully contained by 4-6. In pairs where one assignment fully contains the other, one Elf in the pair would be exclusively cleaning sections their partner will already be cleaning, so these seem like the most in need of reconsideration. In this example, there are 2 such pairs.
 ///
 /// In how many assignment pairs does one range fully contain the other?
-use aoc2022::{Part, Result};
+use aoc2022::{Error, Mode, Options, Part, Result};
 use itertools::Itertools;
-use std::io::{BufRead, BufReader, Read};
+use std::{
+    fmt,
+    io::{BufRead, BufReader, Read},
+    str::FromStr,
+};
 
-pub(crate) fn execute<R: Read>(part: &Part, reader: R) -> Result<()> {
-    match part {
-        Part::Part1 => println!("{}", solve1(reader)),
-        Part::Part2 => println!("{}", solve2(reader)),
+pub(crate) fn execute<R: Read>(part: &Part, options: &Options, reader: R) -> Result<()> {
+    match options.mode {
+        Some(Mode::Coverage) => print!("{}", CoverageReport::new(reader)?),
+        Some(Mode::Render) => print_diagrams(reader)?,
+        Some(mode) => return Err(Error::UnsupportedMode(mode)),
+        None => match part {
+            Part::Part1 => println!("{}", solve1(reader)?),
+            Part::Part2 => println!("{}", solve2(reader)?),
+        },
     };
 
     Ok(())
 }
 
-const BITSET_LEN: u128 = u128::BITS as u128;
+/// An inclusive range of section IDs, like `2-4`.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+struct Sections {
+    start: u64,
+    end: u64,
+}
 
-fn create_bitset(rangeish: &str) -> u128 {
-    let parts: Vec<&str> = rangeish.split('-').collect();
-    let start = parts[0].parse::<u128>().unwrap();
-    let end = parts[1].parse::<u128>().unwrap();
+impl Sections {
+    /// Returns true if every section in `other` is also in this range.
+    fn contains(&self, other: &Sections) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
 
-    (0..BITSET_LEN).fold(0u128, |bits, n| {
-        if n <= end && n >= start {
-            bits | 1 << n
-        } else {
-            bits
+    /// Returns true if there is at least one section in both ranges.
+    fn overlaps(&self, other: &Sections) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    /// The number of sections in the range; a u128, since a range can cover every u64.
+    fn len(&self) -> u128 {
+        u128::from(self.end - self.start) + 1
+    }
+}
+
+impl fmt::Display for Sections {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+impl FromStr for Sections {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let (start, end) = s
+            .split_once('-')
+            .ok_or_else(|| format!("expected a range like 2-4, found '{s}'"))?;
+
+        let id = |id: &str| {
+            id.parse::<u64>()
+                .map_err(|_| format!("'{id}' is not a section ID"))
+        };
+
+        let sections = Sections {
+            start: id(start)?,
+            end: id(end)?,
+        };
+
+        if sections.start > sections.end {
+            return Err(format!("range '{s}' ends before it starts"));
         }
-    })
+
+        Ok(sections)
+    }
+}
+
+/// Parses a single line, like `2-4,6-8`, into the two elves' assignments.
+fn parse_pair(line_number: usize, line: &str) -> Result<(Sections, Sections)> {
+    let parse = |message: String| Error::Parse {
+        line: line_number,
+        message,
+    };
+
+    let (a, b) = line
+        .split_once(',')
+        .ok_or_else(|| parse("expected two ranges separated by a comma".into()))?;
+
+    Ok((a.parse().map_err(parse)?, b.parse().map_err(parse)?))
 }
 
-fn solve_shared<R: Read>(reader: R) -> impl Iterator<Item = std::io::Result<(u128, u128)>> {
+/// Reads the pairs of assignments along with the line each was on, skipping blank lines.
+fn solve_shared<R: Read>(reader: R) -> impl Iterator<Item = Result<(usize, Sections, Sections)>> {
     BufReader::new(reader)
         .lines()
-        .filter_ok(|line| !line.is_empty())
-        .map_ok(|line| {
-            let parts = line.split(',').collect::<Vec<&str>>();
-            match parts[..] {
-                [a, b] => (create_bitset(a), create_bitset(b)),
-                _ => panic!("badly formed line"),
-            }
+        .enumerate()
+        .filter_map(|(n, line)| match line {
+            Ok(line) if line.is_empty() => None,
+            Ok(line) => Some(parse_pair(n + 1, &line).map(|(a, b)| (n + 1, a, b))),
+            Err(err) => Some(Err(err.into())),
         })
 }
 
-fn solve1<R: Read>(reader: R) -> u128 {
-    solve_shared(reader)
-        .fold_ok(0, |previous, (a, b)| {
-            // a & !b == 0 determines if a was entirely consisted of the bits of b; all bits of b are unset on a, so if the result is 0, then a was a subset of b.
-            previous + if a & !b == 0 || b & !a == 0 { 1 } else { 0 }
-        })
-        .unwrap()
+fn solve1<R: Read>(reader: R) -> Result<usize> {
+    solve_shared(reader).fold_ok(0, |previous, (_, a, b)| {
+        previous + usize::from(a.contains(&b) || b.contains(&a))
+    })
+}
+
+fn solve2<R: Read>(reader: R) -> Result<usize> {
+    solve_shared(reader).fold_ok(0, |previous, (_, a, b)| {
+        previous + usize::from(a.overlaps(&b))
+    })
+}
+
+/// A run of consecutive sections that are all assigned to the same number of elves.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+struct Segment {
+    sections: Sections,
+    elves: usize,
+}
+
+/// How every assignment in the camp covers the sections from the lowest ID anyone was given to the highest.
+///
+/// Segments are in order and leave no gaps, with unassigned stretches recorded as segments with no elves. Every assignment starts and ends on a segment boundary, so a segment is either entirely inside an assignment or entirely outside it.
+#[derive(Debug, PartialEq, Eq)]
+struct Coverage {
+    segments: Vec<Segment>,
+}
+
+impl Coverage {
+    fn new(assignments: impl IntoIterator<Item = Sections>) -> Self {
+        // Each assignment adds an elf at its start and takes one away just after its end, which needs a u128 when the end is u64::MAX.
+        let mut changes: Vec<(u128, isize)> = assignments
+            .into_iter()
+            .flat_map(|sections| {
+                [
+                    (u128::from(sections.start), 1),
+                    (u128::from(sections.end) + 1, -1),
+                ]
+            })
+            .collect();
+
+        changes.sort_unstable();
+
+        let mut segments = Vec::new();
+        let mut elves = 0;
+        for (i, (at, change)) in changes.iter().enumerate() {
+            elves += change;
+            match changes.get(i + 1) {
+                Some((next, _)) if next > at => segments.push(Segment {
+                    sections: Sections {
+                        start: *at as u64,
+                        end: (next - 1) as u64,
+                    },
+                    elves: elves as usize,
+                }),
+                _ => {}
+            }
+        }
+
+        Coverage { segments }
+    }
+
+    /// Returns the ranges of sections whose number of elves satisfies `predicate`, merging neighbouring segments together.
+    fn sections_where(&self, predicate: impl Fn(usize) -> bool) -> Vec<Sections> {
+        let mut ranges: Vec<Sections> = Vec::new();
+        for segment in self.segments.iter().filter(|s| predicate(s.elves)) {
+            match ranges.last_mut() {
+                Some(last) if u128::from(last.end) + 1 == u128::from(segment.sections.start) => {
+                    last.end = segment.sections.end;
+                }
+                _ => ranges.push(segment.sections),
+            }
+        }
+
+        ranges
+    }
+
+    /// Returns true if every section assigned to either elf in the pair is also assigned to someone from another pair.
+    fn is_redundant(&self, a: &Sections, b: &Sections) -> bool {
+        let (start, end) = (a.start.min(b.start), a.end.max(b.end));
+        let first = self
+            .segments
+            .partition_point(|segment| segment.sections.end < start);
+
+        self.segments[first..]
+            .iter()
+            .take_while(|segment| segment.sections.start <= end)
+            .all(|segment| {
+                let own = usize::from(a.contains(&segment.sections))
+                    + usize::from(b.contains(&segment.sections));
+                own == 0 || segment.elves > own
+            })
+    }
 }
 
-fn solve2<R: Read>(reader: R) -> u128 {
-    solve_shared(reader)
-        .fold_ok(0, |previous, (a, b)| {
-            // a & b > 0 determines if a and b contained any bits in common.
-            previous + if a & b > 0 { 1 } else { 0 }
+/// A camp-wide view of the assignments, rather than just comparing the two elves in each pair.
+#[derive(Debug, PartialEq, Eq)]
+struct CoverageReport {
+    pairs: usize,
+    /// The lowest and highest sections anyone was assigned.
+    span: Option<Sections>,
+    covered: u128,
+    /// Sections assigned to three or more elves.
+    crowded: Vec<Sections>,
+    /// Sections within the span that nobody was assigned.
+    gaps: Vec<Sections>,
+    /// The line numbers of pairs where every section either of them was assigned is also assigned to someone else.
+    redundant: Vec<usize>,
+}
+
+impl CoverageReport {
+    fn new<R: Read>(reader: R) -> Result<Self> {
+        let pairs: Vec<(usize, Sections, Sections)> = solve_shared(reader).try_collect()?;
+        let coverage = Coverage::new(pairs.iter().flat_map(|(_, a, b)| [*a, *b]));
+
+        let span = match (coverage.segments.first(), coverage.segments.last()) {
+            (Some(first), Some(last)) => Some(Sections {
+                start: first.sections.start,
+                end: last.sections.end,
+            }),
+            _ => None,
+        };
+
+        let covered = coverage
+            .sections_where(|elves| elves > 0)
+            .iter()
+            .map(Sections::len)
+            .sum();
+
+        let redundant = pairs
+            .iter()
+            .filter(|(_, a, b)| coverage.is_redundant(a, b))
+            .map(|(line, _, _)| *line)
+            .collect();
+
+        Ok(CoverageReport {
+            pairs: pairs.len(),
+            span,
+            covered,
+            crowded: coverage.sections_where(|elves| elves >= 3),
+            gaps: coverage.sections_where(|elves| elves == 0),
+            redundant,
         })
-        .unwrap()
+    }
+}
+
+impl fmt::Display for CoverageReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ranges = |ranges: &[Sections]| {
+            let total: u128 = ranges.iter().map(Sections::len).sum();
+            match ranges {
+                [] => "none".to_string(),
+                ranges => format!("{total} ({})", ranges.iter().join(", ")),
+            }
+        };
+
+        writeln!(f, "{:<16}{}", "pairs", self.pairs)?;
+        match self.span {
+            Some(span) => writeln!(f, "{:<16}{span}", "span")?,
+            None => writeln!(f, "{:<16}none", "span")?,
+        }
+
+        writeln!(f, "{:<16}{}", "covered", self.covered)?;
+        writeln!(f, "{:<16}{}", "3+ elves", ranges(&self.crowded))?;
+        writeln!(f, "{:<16}{}", "gaps", ranges(&self.gaps))?;
+        let redundant = match self.redundant.as_slice() {
+            [] => "none".to_string(),
+            lines => format!("{} (lines {})", lines.len(), lines.iter().join(", ")),
+        };
+
+        writeln!(f, "{:<16}{redundant}", "redundant pairs")
+    }
+}
+
+/// The widest a diagram can be while still drawing every section.
+const DIAGRAM_WIDTH: u128 = 100;
+
+/// Draws pairs of assignments the way the puzzle text does, with one row per elf and one column per section:
+///
+///     .2345678.  2-8
+///     ..34567..  3-7
+///       =====    2-8 contains 3-7
+///
+/// A third row marks the sections the two elves share, with `^` where they overlap and `=` where one assignment contains the other.
+/// Pairs are drawn against the same axis where it fits, so they can be compared down the page; see `draw_pairs`.
+///
+/// When there are too many sections to fit, long stretches that no assignment starts or ends inside are cut down to a single column, drawn as `~` if the elf is assigned that stretch and `-` if not.
+/// Every elf covers either all or none of such a stretch, and the sections either side of it are still drawn, so the ends of each assignment stay exact.
+#[derive(Debug, PartialEq, Eq)]
+struct Diagram {
+    /// The sections each column stands for; usually just one.
+    columns: Vec<Sections>,
+}
+
+impl Diagram {
+    fn new<'a>(assignments: impl IntoIterator<Item = &'a Sections>) -> Self {
+        // Every section between two neighbouring boundaries is assigned to exactly the same elves.
+        let mut boundaries = Vec::new();
+        for sections in assignments {
+            boundaries.push(u128::from(sections.start));
+            boundaries.push(u128::from(sections.end) + 1);
+        }
+
+        // Section IDs count from 1, so start there like the puzzle does unless something comes earlier.
+        let start = boundaries.iter().copied().min().unwrap_or(1).min(1);
+        let end = boundaries.iter().copied().max().unwrap_or(1).max(start + 1);
+        boundaries.extend([start, end]);
+        boundaries.sort_unstable();
+        boundaries.dedup();
+
+        let section = |id: u128| u64::try_from(id).expect("boundaries come from u64 section IDs");
+        let single = |id: u128| Sections {
+            start: section(id),
+            end: section(id),
+        };
+
+        let mut columns = Vec::new();
+        for (&from, &to) in boundaries.iter().tuple_windows() {
+            if end - start <= DIAGRAM_WIDTH || to - from <= 3 {
+                columns.extend((from..to).map(single));
+            } else {
+                columns.push(single(from));
+                columns.push(Sections {
+                    start: section(from + 1),
+                    end: section(to - 2),
+                });
+                columns.push(single(to - 1));
+            }
+        }
+
+        Diagram { columns }
+    }
+
+    /// Returns true if any column stands for more than one section.
+    fn is_cut(&self) -> bool {
+        self.columns.iter().any(|column| column.len() > 1)
+    }
+
+    fn row(&self, sections: &Sections) -> String {
+        self.columns
+            .iter()
+            .map(
+                |column| match (sections.contains(column), column.len() > 1) {
+                    (true, true) => '~',
+                    (true, false) => char::from(b'0' + (column.start % 10) as u8),
+                    (false, true) => '-',
+                    (false, false) => '.',
+                },
+            )
+            .collect()
+    }
+
+    /// Returns the row marking what the pair share, and a description of it, if they share anything at all.
+    fn markers(&self, a: &Sections, b: &Sections) -> Option<(String, String)> {
+        if !a.overlaps(b) {
+            return None;
+        }
+
+        let (marker, description) = if a.contains(b) {
+            ('=', format!("{a} contains {b}"))
+        } else if b.contains(a) {
+            ('=', format!("{a} is contained by {b}"))
+        } else {
+            ('^', "overlap".to_string())
+        };
+
+        let shared = Sections {
+            start: a.start.max(b.start),
+            end: a.end.min(b.end),
+        };
+
+        let row = self
+            .columns
+            .iter()
+            .map(|column| if shared.contains(column) { marker } else { ' ' })
+            .collect();
+
+        Some((row, description))
+    }
+
+    fn draw(&self, a: &Sections, b: &Sections) -> String {
+        let mut drawing = format!("{}  {a}\n{}  {b}\n", self.row(a), self.row(b));
+        if let Some((row, description)) = self.markers(a, b) {
+            drawing += &format!("{row}  {description}\n");
+        }
+
+        drawing
+    }
+}
+
+/// Draws every pair, returning a note about how to read the drawings along with them.
+///
+/// Pairs share one axis if it fits in `DIAGRAM_WIDTH` columns. Otherwise each pair gets an axis built from its own assignments, which never needs more than a few columns per assignment once long stretches are cut.
+fn draw_pairs(pairs: &[(Sections, Sections)]) -> (Option<&'static str>, Vec<String>) {
+    const CUT: &str =
+        "long stretches are cut down to one column; ~ where the elf is assigned it, - where not";
+    const SEPARATE: &str = "too many sections to share an axis, so each pair is drawn on its own; long stretches are cut down to one column, ~ where the elf is assigned it, - where not";
+
+    let shared = Diagram::new(pairs.iter().flat_map(|(a, b)| [a, b]));
+    if shared.columns.len() as u128 <= DIAGRAM_WIDTH {
+        let note = shared.is_cut().then_some(CUT);
+        return (note, pairs.iter().map(|(a, b)| shared.draw(a, b)).collect());
+    }
+
+    let drawings = pairs
+        .iter()
+        .map(|(a, b)| Diagram::new([a, b]).draw(a, b))
+        .collect();
+
+    (Some(SEPARATE), drawings)
+}
+
+fn print_diagrams<R: Read>(reader: R) -> Result<()> {
+    let pairs: Vec<(Sections, Sections)> = solve_shared(reader)
+        .map_ok(|(_, a, b)| (a, b))
+        .try_collect()?;
+
+    let (note, drawings) = draw_pairs(&pairs);
+    if let Some(note) = note {
+        println!("{note}\n");
+    }
+
+    print!("{}", drawings.join("\n"));
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{solve1, solve2};
+    use super::{
+        draw_pairs, solve1, solve2, solve_shared, Coverage, CoverageReport, Diagram, Sections,
+        DIAGRAM_WIDTH,
+    };
     const SAMPLE_DOC: &[u8] = b"
 2-4,6-8
 2-3,4-5
@@ -116,11 +488,207 @@ mod tests {
 
     #[test]
     fn solve_part1_works() {
-        assert_eq!(2, solve1(std::io::Cursor::new(SAMPLE_DOC)));
+        assert_eq!(2, solve1(std::io::Cursor::new(SAMPLE_DOC)).unwrap());
     }
 
     #[test]
     fn solve_part2_works() {
-        assert_eq!(4, solve2(std::io::Cursor::new(SAMPLE_DOC)));
+        assert_eq!(4, solve2(std::io::Cursor::new(SAMPLE_DOC)).unwrap());
+    }
+
+    #[test]
+    fn handles_large_section_ids() {
+        let max = u64::MAX;
+        let doc = format!("127-128,128-{max}\n0-{max},{max}-{max}\n1000-2000,2001-3000\n");
+        assert_eq!(1, solve1(doc.as_bytes()).unwrap());
+        assert_eq!(2, solve2(doc.as_bytes()).unwrap());
+
+        let a: Sections = "5-10".parse().unwrap();
+        assert!(a.contains(&a));
+        assert!(a.overlaps(&"10-20".parse().unwrap()));
+        assert!(!a.overlaps(&"11-20".parse().unwrap()));
+    }
+
+    #[test]
+    fn rejects_bad_ranges() {
+        assert!("4-2".parse::<Sections>().is_err());
+        assert!("4".parse::<Sections>().is_err());
+        assert!("a-2".parse::<Sections>().is_err());
+
+        let err = solve1(&b"2-4,6-8\n\n2-4;6-8\n"[..]).unwrap_err();
+        assert_eq!(
+            "line 3: expected two ranges separated by a comma",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn reports_coverage_of_sample() {
+        let report = CoverageReport::new(SAMPLE_DOC).unwrap();
+        assert_eq!(6, report.pairs);
+        assert_eq!(8, report.covered);
+        assert_eq!(vec![Sections { start: 2, end: 8 }], report.crowded);
+        assert!(report.gaps.is_empty());
+        // Only section 9 is unique to one elf, in the pair on line 4.
+        assert_eq!(vec![2, 3, 5, 6, 7], report.redundant);
+        assert_eq!(
+            "pairs           6
+span            2-9
+covered         8
+3+ elves        7 (2-8)
+gaps            none
+redundant pairs 5 (lines 2, 3, 5, 6, 7)
+",
+            report.to_string()
+        );
+    }
+
+    #[test]
+    fn finds_gaps_and_crowding() {
+        let max = u64::MAX;
+        let doc = format!("1-3,5-5\n2-3,{max}-{max}\n3-3,5-6\n");
+        let report = CoverageReport::new(doc.as_bytes()).unwrap();
+        assert_eq!(Some(Sections { start: 1, end: max }), report.span);
+        assert_eq!(6, report.covered);
+        assert_eq!(vec![Sections { start: 3, end: 3 }], report.crowded);
+        assert_eq!(
+            vec![
+                Sections { start: 4, end: 4 },
+                Sections {
+                    start: 7,
+                    end: max - 1
+                }
+            ],
+            report.gaps
+        );
+        assert!(report.redundant.is_empty());
+
+        // Neither elf in the first pair does anything the rest of the camp doesn't, even though they overlap each other.
+        let coverage = Coverage::new(
+            ["1-4", "3-6", "1-2", "5-6", "3-4", "7-8"].map(|s| s.parse::<Sections>().unwrap()),
+        );
+        let pair = |s: &str| s.parse::<Sections>().unwrap();
+        assert!(coverage.is_redundant(&pair("1-4"), &pair("3-6")));
+        assert!(coverage.is_redundant(&pair("1-2"), &pair("5-6")));
+        assert!(!coverage.is_redundant(&pair("5-6"), &pair("7-8")));
+    }
+
+    #[test]
+    fn draws_pairs_like_the_puzzle() {
+        let pairs: Vec<(Sections, Sections)> = solve_shared(SAMPLE_DOC)
+            .map(|pair| pair.map(|(_, a, b)| (a, b)).unwrap())
+            .collect();
+
+        let diagram = Diagram::new(pairs.iter().flat_map(|(a, b)| [a, b]));
+        let drawings: Vec<String> = pairs.iter().map(|(a, b)| diagram.draw(a, b)).collect();
+        assert_eq!(
+            vec![
+                ".234.....  2-4\n.....678.  6-8\n",
+                ".23......  2-3\n...45....  4-5\n",
+                "....567..  5-7\n......789  7-9\n      ^    overlap\n",
+                ".2345678.  2-8\n..34567..  3-7\n  =====    2-8 contains 3-7\n",
+                ".....6...  6-6\n...456...  4-6\n     =     6-6 is contained by 4-6\n",
+                ".23456...  2-6\n...45678.  4-8\n   ^^^     overlap\n",
+            ],
+            drawings
+        );
+    }
+
+    #[test]
+    fn cuts_wide_diagrams_down() {
+        let a = Sections { start: 1, end: 300 };
+        let b = Sections {
+            start: 280,
+            end: u64::MAX,
+        };
+
+        let diagram = Diagram::new([&a, &b]);
+        assert!(diagram.is_cut());
+        assert_eq!(
+            "1~90~0.-.  1-300\n.-.0~01~5  280-18446744073709551615\n   ^^^     overlap\n",
+            diagram.draw(&a, &b)
+        );
+    }
+
+    #[test]
+    fn draws_every_section_of_puzzle_sized_inputs() {
+        let a = Sections { start: 1, end: 1 };
+        let b = Sections { start: 99, end: 99 };
+        let diagram = Diagram::new([&a, &b]);
+        assert!(!diagram.is_cut());
+        assert_eq!(99, diagram.columns.len());
+    }
+
+    #[test]
+    fn never_draws_adjacent_ranges_in_the_same_column() {
+        let shares_a_column = |diagram: &Diagram, a: &Sections, b: &Sections| {
+            diagram
+                .row(a)
+                .chars()
+                .zip(diagram.row(b).chars())
+                .any(|(x, y)| !matches!(x, '.' | '-') && !matches!(y, '.' | '-'))
+        };
+
+        let pairs = [
+            (Sections { start: 2, end: 3 }, Sections { start: 4, end: 5 }),
+            (
+                Sections {
+                    start: 1000,
+                    end: 5000,
+                },
+                Sections {
+                    start: 5001,
+                    end: 90000,
+                },
+            ),
+        ];
+
+        let diagram = Diagram::new(pairs.iter().flat_map(|(a, b)| [a, b]));
+        assert!(diagram.is_cut());
+        for (a, b) in &pairs {
+            assert!(!shares_a_column(&diagram, a, b));
+            assert_eq!(None, diagram.markers(a, b));
+            assert!(!shares_a_column(&Diagram::new([a, b]), a, b));
+        }
+    }
+
+    #[test]
+    fn keeps_rows_narrow_for_many_wide_pairs() {
+        // A simple linear congruential generator, so the test sees the same thousand pairs every time.
+        let mut seed: u64 = 1;
+        let mut id = || {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) % 1_000_000 + 1
+        };
+
+        let pairs: Vec<(Sections, Sections)> = (0..1000)
+            .map(|_| {
+                let mut range = || {
+                    let (x, y) = (id(), id());
+                    Sections {
+                        start: x.min(y),
+                        end: x.max(y),
+                    }
+                };
+                (range(), range())
+            })
+            .collect();
+
+        let (note, drawings) = draw_pairs(&pairs);
+        assert!(note.is_some());
+        assert_eq!(1000, drawings.len());
+        for drawing in &drawings {
+            let width = drawing.find("  ").unwrap();
+            assert!(width as u128 <= DIAGRAM_WIDTH, "{drawing}");
+        }
+
+        // Pairs that fit are still drawn on one axis.
+        let narrow = [(Sections { start: 2, end: 3 }, Sections { start: 4, end: 5 })];
+        assert_eq!(
+            (None, vec![".23..  2-3\n...45  4-5\n".to_string()]),
+            draw_pairs(&narrow)
+        );
     }
 }
@@ -1,212 +1,460 @@
-// https://adventofcode.com/2022/day/2
-use aoc2022::{Part, Result};
+// https://adventofcode.com/2022/day/5
+use aoc2022::{Error, Mode, Options, Part, Result};
 use itertools::Itertools;
 use std::{
     collections::HashMap,
+    fmt,
     io::{BufRead, BufReader, Read},
+    str::FromStr,
 };
 
-pub(crate) fn execute<R: Read>(part: &Part, reader: R) -> Result<()> {
-    match part {
-        Part::Part1 => println!("{}", solve1(reader).unwrap()),
-        Part::Part2 => println!("{}", solve2(reader).unwrap()),
+pub(crate) fn execute<R: Read>(part: &Part, options: &Options, reader: R) -> Result<()> {
+    let crane: Box<dyn Crane> = match (part, options.capacity) {
+        (Part::Part1, _) => Box::new(CrateMover9000),
+        (Part::Part2, None) => Box::new(CrateMover9001),
+        (Part::Part2, Some(0)) => {
+            return Err(Error::InvalidInput("--capacity must be at least 1".into()))
+        }
+        (Part::Part2, Some(capacity)) => Box::new(LimitedCrane(capacity)),
+    };
+
+    let mut cargo = Cargo::parse(reader)?;
+    let moves = cargo.moves.len();
+    match options.mode {
+        Some(Mode::Render) => {
+            let steps = options.steps.unwrap_or(moves);
+            if steps > moves {
+                return Err(Error::InvalidInput(format!(
+                    "--steps is {steps}, but there are only {moves} moves"
+                )));
+            }
+
+            cargo.run(crane.as_ref(), steps)?;
+            print!("{cargo}");
+        }
+        Some(mode) => return Err(Error::UnsupportedMode(mode)),
+        None => {
+            cargo.run(crane.as_ref(), moves)?;
+            println!("{}", cargo.tops()?);
+        }
     };
 
     Ok(())
 }
 
-enum Mode {
-    Instructions,
-    Assignments,
-}
-
-fn solve1<R: Read>(reader: R) -> Result<String> {
-    // Parse crate storage first.
-    // The first n lines before a line that begins with a whitespace then a number are the crate allocations
-    let buf = BufReader::new(reader);
-    let mut assignments = Vec::new();
-    let mut instructions = Vec::new();
-    let mut mode = Mode::Assignments;
-    for line in buf.lines() {
-        let line = line.unwrap();
-        match mode {
-            Mode::Assignments => {
-                if !assignments.is_empty() && line.is_empty() {
-                    mode = Mode::Instructions;
-                }
+/// A crate, as an index into the labels of every crate in the cargo.
+///
+/// Labels are usually a single letter, but can be anything that fits between the brackets. Moving small numbers around rather than the labels themselves keeps big moves cheap.
+type Crate = u32;
 
-                assignments.push(line);
-            }
-            Mode::Instructions => {
-                instructions.push(line);
-            }
-        };
+/// A single instruction from the crane program, like `move 1 from 2 to 1`.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+struct Move {
+    count: usize,
+    from: u32,
+    to: u32,
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "move {} from {} to {}", self.count, self.from, self.to)
     }
+}
 
-    // Each bucket will contain a Vec<string>. Each column in an assignment corresponds to one bucket.
-    let mut buckets = HashMap::new();
-    for line in assignments {
-        if line.is_empty() {
-            continue;
+/// Parses an instruction like `move 1 from 2 to 1`.
+fn parse_move(line: usize, instruction: &str) -> Result<Move> {
+    fn number<T: FromStr>(line: usize, token: &str) -> Result<T> {
+        token.parse().map_err(|_| Error::Parse {
+            line,
+            message: format!("'{token}' is not a number"),
+        })
+    }
+
+    // One more token than an instruction should have, so that anything trailing is caught.
+    let mut tokens = instruction.split_whitespace();
+    let tokens: [Option<&str>; 7] = std::array::from_fn(|_| tokens.next());
+    match tokens {
+        [Some("move"), Some(count), Some("from"), Some(from), Some("to"), Some(to), None] => {
+            Ok(Move {
+                count: number(line, count)?,
+                from: number(line, from)?,
+                to: number(line, to)?,
+            })
         }
+        _ => Err(Error::Parse {
+            line,
+            message: format!(
+                "expected an instruction like 'move 1 from 2 to 3', found '{instruction}'"
+            ),
+        }),
+    }
+}
 
-        // dirty hack: we know how many columns to expect because each column is 3 characters with one character gutter
-        for col in (0..line.len()).step_by(4) {
-            let index = (col + 4) / 4;
-            let start = col;
-            let end = col + 3;
-            let part = line[start..end].trim();
-            if part.is_empty() {
-                continue;
-            }
+/// The stacks of crates as drawn at the top of the puzzle input, and the moves the crane is going to make.
+#[derive(Debug, PartialEq, Eq)]
+struct Cargo {
+    /// The number under each stack in the drawing, in ascending order.
+    labels: Vec<u32>,
+    /// Each stack is listed bottom crate first, in the same order as `labels`.
+    stacks: Vec<Vec<Crate>>,
+    /// The label of every crate, in the order they were first seen working left to right and bottom to top.
+    names: Vec<String>,
+    moves: Vec<Move>,
+}
 
-            // If this character is a number, break out - we're not on the assignment line anymore.
-            if part.parse::<u32>().is_ok() {
-                break;
-            }
+/// A machine that can move crates between stacks. Cranes differ only in the order the crates end up in.
+trait Crane {
+    /// Puts the crates in `lifted`, which came off the top of a stack bottom crate first, onto the top of `stack`; leaving `lifted` empty.
+    fn put_down(&self, lifted: &mut Vec<Crate>, stack: &mut Vec<Crate>);
+}
 
-            let letter = part.chars().nth(1).unwrap();
-            buckets
-                .entry(index as u32)
-                .or_insert(Vec::new())
-                .push(letter);
-        }
+/// Moves one crate at a time, so the crates that are moved end up in reverse order.
+struct CrateMover9000;
+
+impl Crane for CrateMover9000 {
+    fn put_down(&self, lifted: &mut Vec<Crate>, stack: &mut Vec<Crate>) {
+        stack.extend(lifted.drain(..).rev());
     }
+}
+
+/// Moves every crate at once, so the crates that are moved keep their order.
+struct CrateMover9001;
 
-    // We need to reverse the buckets because they are currently in the wrong order;
-    // we are reading the list from the top-down.
-    for bucket in buckets.values_mut() {
-        bucket.reverse()
+impl Crane for CrateMover9001 {
+    fn put_down(&self, lifted: &mut Vec<Crate>, stack: &mut Vec<Crate>) {
+        stack.append(lifted);
     }
+}
 
-    // process each instruction
-    for instr in instructions {
-        // each instruction is in a predictable format;
-        // move N from X to Y
-        // The crates act like a stack.
-        let parts: Vec<&str> = instr.split(' ').collect();
-        let count: u32 = parts[1].parse().unwrap();
-        let src: u32 = parts[3].parse().unwrap();
-        let dst: u32 = parts[5].parse().unwrap();
-        let mut popped = Vec::new();
-        let source = buckets.entry(src).or_default();
-        for _ in 1..=count {
-            popped.push(source.pop().unwrap());
+/// Moves up to a given number of crates at once, keeping the order of each load.
+struct LimitedCrane(usize);
+
+impl Crane for LimitedCrane {
+    fn put_down(&self, lifted: &mut Vec<Crate>, stack: &mut Vec<Crate>) {
+        while !lifted.is_empty() {
+            let load = lifted.len().saturating_sub(self.0);
+            stack.extend(lifted.drain(load..));
         }
+    }
+}
 
-        let dst = buckets.entry(dst).or_default();
-        // We replace them back on in reverse order, as they are transferred one by one and popped is currently a LIFO stack
-        for el in popped {
-            dst.push(el);
+impl Cargo {
+    fn parse<R: Read>(reader: R) -> Result<Self> {
+        // The drawing runs up to the first blank line, and everything after that is instructions.
+        let mut drawing = Vec::new();
+        let mut moves = Vec::new();
+        let mut in_drawing = true;
+        let mut reader = BufReader::new(reader);
+        let mut line = String::new();
+        let mut line_number = 0;
+        while reader.read_line(&mut line)? > 0 {
+            line_number += 1;
+            let trimmed = line.trim_end_matches(['\n', '\r']);
+            if in_drawing {
+                if !trimmed.trim().is_empty() {
+                    drawing.push((line_number, trimmed.to_string()));
+                } else if !drawing.is_empty() {
+                    in_drawing = false;
+                }
+            } else if !trimmed.trim().is_empty() {
+                moves.push(parse_move(line_number, trimmed)?);
+            }
+
+            line.clear();
         }
+
+        Ok(Cargo::new(parse_drawing(&drawing)?, moves))
     }
 
-    let mut top = Vec::new();
-    let sorted_keys = buckets.keys().sorted();
-    for key in sorted_keys {
-        let bucket = buckets.get(key).unwrap();
-        top.push(bucket.last().unwrap());
+    fn new(stacks: impl IntoIterator<Item = (u32, Vec<String>)>, moves: Vec<Move>) -> Self {
+        let mut labels = Vec::new();
+        let mut names = Vec::new();
+        let mut ids = HashMap::new();
+        let stacks = stacks
+            .into_iter()
+            .sorted_by_key(|(label, _)| *label)
+            .map(|(label, crates)| {
+                labels.push(label);
+                crates
+                    .into_iter()
+                    .map(|name| {
+                        *ids.entry(name).or_insert_with_key(|name| {
+                            names.push(name.clone());
+                            names.len() as Crate - 1
+                        })
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Cargo {
+            labels,
+            stacks,
+            names,
+            moves,
+        }
     }
 
-    Ok(top.into_iter().collect::<String>())
-}
-fn solve2<R: Read>(reader: R) -> Result<String> {
-    // Parse crate storage first.
-    // The first n lines before a line that begins with a whitespace then a number are the crate allocations
-    let buf = BufReader::new(reader);
-    let mut assignments = Vec::new();
-    let mut instructions = Vec::new();
-    let mut mode = Mode::Assignments;
-    for line in buf.lines() {
-        let line = line.unwrap();
-        match mode {
-            Mode::Assignments => {
-                if !assignments.is_empty() && line.is_empty() {
-                    mode = Mode::Instructions;
-                }
+    /// Returns the position in `stacks` of the stack with the given number.
+    fn index(&self, label: u32) -> Option<usize> {
+        self.labels.binary_search(&label).ok()
+    }
 
-                assignments.push(line);
-            }
-            Mode::Instructions => {
-                instructions.push(line);
+    fn name(&self, id: Crate) -> &str {
+        &self.names[id as usize]
+    }
+
+    #[cfg(test)]
+    fn stack(&self, label: u32) -> Vec<&str> {
+        self.stacks[self.index(label).unwrap()]
+            .iter()
+            .map(|id| self.name(*id))
+            .collect()
+    }
+
+    /// Carries out the first `steps` moves with the given crane, stopping at the first move that can't be made.
+    ///
+    /// Crates are moved in bulk rather than one at a time, so the cost of a move is in the number of crates rather than in bookkeeping.
+    fn run(&mut self, crane: &dyn Crane, steps: usize) -> std::result::Result<(), CraneError> {
+        let mut lifted = Vec::new();
+        for (n, step) in self.moves[..steps].iter().enumerate() {
+            let error = |problem| CraneError {
+                instruction: n + 1,
+                step: *step,
+                problem,
+                state: self.to_string(),
+            };
+
+            let Some(from) = self.index(step.from) else {
+                return Err(error(Problem::UnknownStack(step.from)));
+            };
+
+            let Some(to) = self.index(step.to) else {
+                return Err(error(Problem::UnknownStack(step.to)));
+            };
+
+            let height = self.stacks[from].len();
+            if height < step.count {
+                return Err(error(Problem::Underflow(height)));
             }
-        };
+
+            lifted.extend(self.stacks[from].drain(height - step.count..));
+            crane.put_down(&mut lifted, &mut self.stacks[to]);
+        }
+
+        Ok(())
     }
 
-    // Each bucket will contain a Vec<string>. Each column in an assignment corresponds to one bucket.
-    let mut buckets = HashMap::new();
-    for line in assignments {
-        if line.is_empty() {
-            continue;
+    /// The crate on top of each stack, in stack order.
+    fn tops(&self) -> Result<String> {
+        let empty: Vec<&u32> = self
+            .labels
+            .iter()
+            .zip(&self.stacks)
+            .filter(|(_, stack)| stack.is_empty())
+            .map(|(label, _)| label)
+            .collect();
+
+        if !empty.is_empty() {
+            return Err(Error::InvalidInput(format!(
+                "nothing is on top of empty stacks {}\n{self}",
+                empty.iter().join(", ")
+            )));
         }
 
-        // dirty hack: we know how many columns to expect because each column is 3 characters with one character gutter
-        for col in (0..line.len()).step_by(4) {
-            let index = (col + 4) / 4;
-            let start = col;
-            let end = col + 3;
-            let part = line[start..end].trim();
-            if part.is_empty() {
-                continue;
-            }
+        Ok(self
+            .stacks
+            .iter()
+            .filter_map(|stack| stack.last())
+            .map(|id| self.name(*id))
+            .join(""))
+    }
+}
 
-            // If this character is a number, break out - we're not on the assignment line anymore.
-            if part.parse::<u32>().is_ok() {
-                break;
-            }
+/// Why a move couldn't be made.
+#[derive(Debug, PartialEq, Eq)]
+enum Problem {
+    UnknownStack(u32),
+    /// The move asked for more crates than the stack held, which was this many.
+    Underflow(usize),
+}
 
-            let letter = part.chars().nth(1).unwrap();
-            buckets
-                .entry(index as u32)
-                .or_insert(Vec::new())
-                .push(letter);
+/// A move that couldn't be made, along with a drawing of the stacks just before it.
+#[derive(Debug, PartialEq, Eq)]
+struct CraneError {
+    /// The position of the move in the program, counting from 1.
+    instruction: usize,
+    step: Move,
+    problem: Problem,
+    state: String,
+}
+
+impl fmt::Display for CraneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "instruction {} ({}): ", self.instruction, self.step)?;
+        match self.problem {
+            Problem::UnknownStack(stack) => writeln!(f, "there is no stack {stack}")?,
+            Problem::Underflow(height) => writeln!(
+                f,
+                "stack {} only has {height} crate{}",
+                self.step.from,
+                if height == 1 { "" } else { "s" }
+            )?,
         }
+
+        write!(f, "{}", self.state)
     }
+}
 
-    // We need to reverse the buckets because they are currently in the wrong order;
-    // we are reading the list from the top-down.
-    for bucket in buckets.values_mut() {
-        bucket.reverse()
+impl From<CraneError> for Error {
+    fn from(err: CraneError) -> Self {
+        Error::InvalidInput(err.to_string())
     }
+}
 
-    // process each instruction
-    for instr in instructions {
-        // each instruction is in a predictable format;
-        // move N from X to Y
-        // The crates act like a stack.
-        let parts: Vec<&str> = instr.split(' ').collect();
-        let count: u32 = parts[1].parse().unwrap();
-        let src: u32 = parts[3].parse().unwrap();
-        let dst: u32 = parts[5].parse().unwrap();
-        let mut popped = Vec::new();
-        let source = buckets.entry(src).or_default();
-        for _ in 1..=count {
-            popped.push(source.pop().unwrap());
+/// Draws the stacks as they are now, in the same format as the drawing at the top of the puzzle input; the moves aren't included.
+///
+/// Every column is wide enough for its widest crate and its stack number, and every row is padded out to the full width, just like the puzzle's own drawings.
+impl fmt::Display for Cargo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let widths: Vec<usize> = self
+            .labels
+            .iter()
+            .zip(&self.stacks)
+            .map(|(label, stack)| {
+                stack
+                    .iter()
+                    .map(|id| self.name(*id).chars().count() + 2)
+                    // Even an empty stack is as wide as a single-letter crate, like in the puzzle.
+                    .chain([label.to_string().len(), 3])
+                    .max()
+                    .unwrap_or_default()
+            })
+            .collect();
+
+        let height = self.stacks.iter().map(Vec::len).max().unwrap_or(0);
+        for row in (0..height).rev() {
+            let cells =
+                self.stacks
+                    .iter()
+                    .zip(&widths)
+                    .map(|(stack, width)| match stack.get(row) {
+                        Some(id) => format!("{:^width$}", format!("[{}]", self.name(*id))),
+                        None => " ".repeat(*width),
+                    });
+
+            writeln!(f, "{}", cells.format(" "))?;
         }
 
-        let dst = buckets.entry(dst).or_default();
-        // This is the key difference in part 2; we apply the stack in the reverse order.
-        popped.reverse();
-        for el in popped {
-            dst.push(el);
+        let index = self
+            .labels
+            .iter()
+            .zip(&widths)
+            .map(|(label, width)| format!("{label:^width$}"));
+
+        writeln!(f, "{}", index.format(" "))
+    }
+}
+
+/// Splits a line of the drawing into its whitespace-separated tokens, with the columns each one starts and ends at.
+fn tokens(line: &str) -> Vec<(usize, usize, &str)> {
+    let mut tokens = Vec::new();
+    let mut chars = line.char_indices().enumerate().peekable();
+    while let Some((start, (offset, c))) = chars.next() {
+        if c.is_whitespace() {
+            continue;
+        }
+
+        let mut end = (start + 1, offset + c.len_utf8());
+        while let Some((column, (offset, c))) = chars.next_if(|(_, (_, c))| !c.is_whitespace()) {
+            end = (column + 1, offset + c.len_utf8());
+        }
+
+        tokens.push((start, end.0, &line[offset..end.1]));
+    }
+
+    tokens
+}
+
+/// Reads the stacks out of a drawing like the one at the top of the puzzle input:
+///
+///         [D]
+///     [N] [C]
+///     [Z] [M] [P]
+///      1   2   3
+///
+/// The last line numbers the stacks, and each crate belongs to whichever stack's number sits beneath it; that way stacks can have any number, and crates any label, so long as they line up.
+fn parse_drawing(drawing: &[(usize, String)]) -> Result<HashMap<u32, Vec<String>>> {
+    let Some(((index_line, index_row), rows)) = drawing.split_last() else {
+        return Err(Error::InvalidInput(
+            "there is no drawing of the stacks".into(),
+        ));
+    };
+
+    let error = |line: usize, message: String| Error::Parse { line, message };
+    let mut labels = Vec::new();
+    let mut stacks = HashMap::new();
+    for (start, end, token) in tokens(index_row) {
+        let label: u32 = token
+            .parse()
+            .map_err(|_| error(*index_line, format!("'{token}' is not a stack number")))?;
+
+        if stacks.insert(label, Vec::new()).is_some() {
+            return Err(error(
+                *index_line,
+                format!("stack {label} is numbered twice"),
+            ));
         }
+
+        labels.push((start, end, label));
     }
 
-    let mut top = Vec::new();
-    let sorted_keys = buckets.keys().sorted();
-    for key in sorted_keys {
-        let bucket = buckets.get(key).unwrap();
-        top.push(bucket.last().unwrap());
+    // Work up from the bottom of the drawing, so each stack is listed bottom crate first.
+    for (line, row) in rows.iter().rev() {
+        for (start, end, token) in tokens(row) {
+            let name = token
+                .strip_prefix('[')
+                .and_then(|token| token.strip_suffix(']'))
+                .filter(|name| !name.is_empty())
+                .ok_or_else(|| {
+                    error(*line, format!("expected a crate like [A], found '{token}'"))
+                })?;
+
+            let mut below = labels
+                .iter()
+                .filter(|(first, last, _)| *first < end && start < *last);
+
+            let label = match (below.next(), below.next()) {
+                (Some((_, _, label)), None) => label,
+                (None, _) => {
+                    return Err(error(
+                        *line,
+                        format!("crate {token} isn't above a stack number"),
+                    ))
+                }
+                (Some(_), Some(_)) => {
+                    return Err(error(
+                        *line,
+                        format!("crate {token} is above more than one stack number"),
+                    ))
+                }
+            };
+
+            stacks.entry(*label).or_default().push(name.to_string());
+        }
     }
 
-    Ok(top.into_iter().collect::<String>())
+    Ok(stacks)
 }
 
 #[cfg(test)]
 mod tests {
     use std::io::Cursor;
 
-    use super::{solve1, solve2};
+    use super::{
+        Cargo, Crane, CraneError, CrateMover9000, CrateMover9001, LimitedCrane, Move, Problem,
+    };
+    use proptest::prelude::*;
 
     const TEST_DOCUMENT: &[u8] = b"
     [D]
@@ -219,19 +467,231 @@ move 3 from 1 to 3
 move 2 from 2 to 1
 move 1 from 1 to 2";
 
+    fn solve(crane: &dyn Crane) -> String {
+        let mut cargo = Cargo::parse(Cursor::new(TEST_DOCUMENT)).unwrap();
+        let moves = cargo.moves.len();
+        cargo.run(crane, moves).unwrap();
+        cargo.tops().unwrap()
+    }
+
     #[test]
-    fn solve1_works() {
+    fn parses_cargo() {
+        let cargo = Cargo::parse(Cursor::new(TEST_DOCUMENT)).unwrap();
+        assert_eq!(vec!["Z", "N"], cargo.stack(1));
+        assert_eq!(vec!["M", "C", "D"], cargo.stack(2));
+        assert_eq!(vec!["P"], cargo.stack(3));
         assert_eq!(
-            String::from("CMZ"),
-            solve1(Cursor::new(TEST_DOCUMENT)).unwrap()
+            Move {
+                count: 3,
+                from: 1,
+                to: 3
+            },
+            cargo.moves[1]
         );
+        assert_eq!(4, cargo.moves.len());
+    }
+
+    #[test]
+    fn solve1_works() {
+        assert_eq!(String::from("CMZ"), solve(&CrateMover9000));
     }
 
     #[test]
     fn solve2_works() {
+        assert_eq!(String::from("MCD"), solve(&CrateMover9001));
+    }
+
+    #[test]
+    fn limited_crane_moves_in_loads() {
+        let mut lifted = vec![1, 2, 3, 4];
+        let mut stack = vec![0];
+        LimitedCrane(2).put_down(&mut lifted, &mut stack);
+        assert_eq!(vec![0, 3, 4, 1, 2], stack);
+        assert!(lifted.is_empty());
+
+        // A crane that can only carry one crate is a CrateMover 9000, and one that can carry everything is a CrateMover 9001.
+        assert_eq!(solve(&CrateMover9000), solve(&LimitedCrane(1)));
+        assert_eq!(solve(&CrateMover9001), solve(&LimitedCrane(3)));
+    }
+
+    #[test]
+    fn parses_wide_drawings() {
+        let document = b"
+[AB]                                   [Z]
+[CD] [E]                        [Q]    [Y]
+  1   2   3   4   5   6   7   8  9  10  11
+
+move 1 from 1 to 11
+move 1 from 9 to 10
+";
+
+        let mut cargo = Cargo::parse(Cursor::new(document)).unwrap();
+        assert_eq!(11, cargo.stacks.len());
+        assert_eq!(vec!["CD", "AB"], cargo.stack(1));
+        assert_eq!(vec!["E"], cargo.stack(2));
+        assert_eq!(vec!["Q"], cargo.stack(9));
+        assert_eq!(vec!["Y", "Z"], cargo.stack(11));
+        assert!(cargo.stack(3).is_empty());
+
+        cargo.run(&CrateMover9000, 2).unwrap();
+        assert_eq!(vec!["CD"], cargo.stack(1));
+        assert_eq!(vec!["Q"], cargo.stack(10));
+        assert_eq!(vec!["Y", "Z", "AB"], cargo.stack(11));
+        assert!(cargo.tops().is_err());
+    }
+
+    #[test]
+    fn rejects_bad_drawings() {
+        let parse =
+            |document: &str| Cargo::parse(Cursor::new(document)).map_err(|err| err.to_string());
+        // Ragged lines, shorter or longer than the index row, are fine so long as the crates line up.
+        assert!(parse("[A]\n[B] [C]   \n 1   2\n\nmove 1 from 1 to 2\n").is_ok());
+        assert_eq!(
+            Err("line 1: crate [C] isn't above a stack number".to_string()),
+            parse("        [C]\n[A] [B]\n 1   2\n")
+        );
+        assert_eq!(
+            Err("line 1: expected a crate like [A], found 'A'".to_string()),
+            parse("A\n1\n")
+        );
+        assert_eq!(
+            Err("line 2: 'x' is not a stack number".to_string()),
+            parse("[A]\n 1 x\n")
+        );
+    }
+
+    #[test]
+    fn draws_stacks_like_the_puzzle() {
+        let drawing = "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 \n";
+        let mut cargo = Cargo::parse(Cursor::new(drawing)).unwrap();
+        assert_eq!(drawing, cargo.to_string());
+
+        let mut cargo_with_moves = Cargo::parse(Cursor::new(TEST_DOCUMENT)).unwrap();
+        cargo_with_moves.run(&CrateMover9000, 1).unwrap();
+        cargo.stacks = cargo_with_moves.stacks;
+        assert_eq!(
+            "[D]        \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 \n",
+            cargo.to_string()
+        );
+
+        let wide = "[AB]                                   [Z]\n[CD] [E]                        [Q]    [Y]\n  1   2   3   4   5   6   7   8  9  10  11\n";
+        let cargo = Cargo::parse(Cursor::new(wide)).unwrap();
+        assert_eq!(
+            "[AB]                                     [Z]\n[CD] [E]                         [Q]     [Y]\n 1    2   3   4   5   6   7   8   9  10  11 \n",
+            cargo.to_string()
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn drawings_round_trip(
+            stacks in prop::collection::btree_map(
+                1u32..200,
+                prop::collection::vec("[A-Z]{1,3}", 0..6),
+                1..12,
+            )
+        ) {
+            let cargo = Cargo::new(stacks, Vec::new());
+
+            let drawing = cargo.to_string();
+            let parsed = Cargo::parse(Cursor::new(drawing.as_bytes())).unwrap();
+            prop_assert_eq!(&cargo, &parsed);
+            prop_assert_eq!(drawing, parsed.to_string());
+        }
+    }
+
+    #[test]
+    fn rejects_bad_instructions() {
+        let parse = |instruction: &str| {
+            let document = format!("[A]\n 1\n\nmove 1 from 1 to 1\n{instruction}\n");
+            Cargo::parse(Cursor::new(document)).map_err(|err| err.to_string())
+        };
+
+        assert!(parse("  move 0 from 1 to 1  ").is_ok());
         assert_eq!(
-            String::from("MCD"),
-            solve2(Cursor::new(TEST_DOCUMENT)).unwrap()
+            Err("line 5: 'x' is not a number".to_string()),
+            parse("move 1 from x to 1")
         );
+        assert_eq!(
+            Err("line 5: '-1' is not a number".to_string()),
+            parse("move -1 from 1 to 1")
+        );
+        assert_eq!(
+            Err(
+                "line 5: expected an instruction like 'move 1 from 2 to 3', found 'move 1 to 2'"
+                    .to_string()
+            ),
+            parse("move 1 to 2")
+        );
+    }
+
+    #[test]
+    fn reports_illegal_moves() {
+        let mut cargo = Cargo::parse(Cursor::new(TEST_DOCUMENT)).unwrap();
+        cargo.moves.push(Move {
+            count: 1,
+            from: 2,
+            to: 4,
+        });
+        let err = cargo.run(&CrateMover9000, 5).unwrap_err();
+        assert_eq!(5, err.instruction);
+        assert_eq!(Problem::UnknownStack(4), err.problem);
+        assert_eq!(
+            "        [Z]\n        [N]\n        [D]\n[C] [M] [P]\n 1   2   3 \n",
+            err.state
+        );
+        // Everything up to the bad move has been done.
+        assert_eq!("CMZ", cargo.tops().unwrap());
+
+        let mut cargo = Cargo::parse(Cursor::new(TEST_DOCUMENT)).unwrap();
+        cargo.moves[1].count = 4;
+        assert_eq!(
+            Err(CraneError {
+                instruction: 2,
+                step: Move {
+                    count: 4,
+                    from: 1,
+                    to: 3
+                },
+                problem: Problem::Underflow(3),
+                state: "[D]        \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 \n".to_string(),
+            }),
+            cargo.run(&CrateMover9001, 4)
+        );
+        assert!(cargo.run(&CrateMover9001, 1).is_ok());
+
+        let mut cargo = Cargo::parse(Cursor::new("[A]\n 1   2\n\nmove 2 from 1 to 2\n")).unwrap();
+        let err = cargo.run(&CrateMover9000, 1).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("instruction 1 (move 2 from 1 to 2): stack 1 only has 1 crate\n"));
+    }
+
+    #[test]
+    fn reports_empty_stacks() {
+        let mut cargo =
+            Cargo::parse(Cursor::new("[A]\n 1   2   3\n\nmove 1 from 1 to 3\n")).unwrap();
+        cargo.run(&CrateMover9000, 1).unwrap();
+        let err = cargo.tops().unwrap_err().to_string();
+        assert!(err.starts_with("nothing is on top of empty stacks 1, 2\n"));
+    }
+
+    #[test]
+    fn runs_long_programs() {
+        let mut document = String::from("[A]\n[B]\n[C] [D]\n 1   2\n\n");
+        for _ in 0..50_000 {
+            document += "move 3 from 1 to 2\nmove 3 from 2 to 1\n";
+        }
+
+        document += "move 2 from 1 to 2\n";
+        let mut cargo = Cargo::parse(Cursor::new(document)).unwrap();
+        cargo.run(&CrateMover9000, cargo.moves.len()).unwrap();
+        assert_eq!("CB", cargo.tops().unwrap());
+
+        cargo.stacks = Cargo::parse(Cursor::new("[A]\n[B]\n[C] [D]\n 1   2\n"))
+            .unwrap()
+            .stacks;
+        cargo.run(&CrateMover9001, cargo.moves.len()).unwrap();
+        assert_eq!("CA", cargo.tops().unwrap());
     }
 }
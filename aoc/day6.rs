@@ -1,63 +1,138 @@
-// https://adventofcode.com/2022/day/2
-use aoc2022::{Part, Result};
-use itertools::Itertools;
-use std::io::Read;
-
-pub(crate) fn execute<R: Read>(part: &Part, mut reader: R) -> Result<()> {
-    let mut lines: String = "".into();
-    reader.read_to_string(&mut lines).unwrap();
-
-    match part {
-        Part::Part1 => println!("{}", solve1(&lines).unwrap()),
-        Part::Part2 => println!("{}", solve2(&lines).unwrap()),
+// https://adventofcode.com/2022/day/6
+use aoc2022::{Error, Part, Result};
+use std::io::{BufRead, BufReader, Read};
+
+pub(crate) fn execute<R: Read>(part: &Part, reader: R) -> Result<()> {
+    let marker = match part {
+        Part::Part1 => solve1(reader)?,
+        Part::Part2 => solve2(reader)?,
     };
 
+    let position =
+        marker.ok_or_else(|| Error::InvalidInput("no marker in the datastream".into()))?;
+    println!("{position}");
     Ok(())
 }
 
-fn solve(input: &str, unique: usize) -> Option<u32> {
-    let start: Vec<char> = input.chars().take(unique).collect();
-    let rest = input.chars().skip(unique);
-    let initial = (unique, start, None);
-    let n = rest.into_iter().fold(initial, |(n, previous, value), c| {
-        let new = [&previous[1..unique], &[c][..]].concat();
-        match (n, new, value) {
-            (n, next, value @ Some(_)) => (n, next, value),
-            (n, next, _) if next.iter().unique().count() == unique => (n + 1, next, Some(n + 1)),
-            (n, next, _) => (n + 1, next, None),
+/// Returns how many bytes have been read when the last `window` bytes are first all different.
+///
+/// The window keeps a count of each byte in it along with how many of those counts are above one, so each byte costs the same however long the window is.
+/// Reading stops as soon as the marker is found, and the stream is never held in memory.
+fn solve<R: Read>(reader: R, window: usize) -> Result<Option<usize>> {
+    if window == 0 {
+        return Ok(Some(0));
+    }
+
+    let mut reader = BufReader::new(reader);
+    let mut recent = vec![0u8; window];
+    let mut counts = [0usize; 256];
+    let mut duplicates = 0;
+    let mut position = 0;
+    loop {
+        let buffer = reader.fill_buf()?;
+        if buffer.is_empty() {
+            return Ok(None);
+        }
+
+        let length = buffer.len();
+        for &byte in buffer {
+            let slot = position % window;
+            if position >= window {
+                let old = recent[slot] as usize;
+                counts[old] -= 1;
+                if counts[old] == 1 {
+                    duplicates -= 1;
+                }
+            }
+
+            recent[slot] = byte;
+            counts[byte as usize] += 1;
+            if counts[byte as usize] == 2 {
+                duplicates += 1;
+            }
+
+            position += 1;
+            if position >= window && duplicates == 0 {
+                return Ok(Some(position));
+            }
         }
-    });
 
-    n.2.map(|m| m as u32)
+        reader.consume(length);
+    }
 }
 
-fn solve1(input: &str) -> Option<u32> {
-    solve(input, 4)
+fn solve1<R: Read>(reader: R) -> Result<Option<usize>> {
+    solve(reader, 4)
 }
 
-fn solve2(input: &str) -> Option<u32> {
-    solve(input, 14)
+fn solve2<R: Read>(reader: R) -> Result<Option<usize>> {
+    solve(reader, 14)
 }
 
 #[cfg(test)]
-
 mod tests {
-    use super::{solve1, solve2};
+    use super::{solve, solve1, solve2};
 
     #[test]
     fn solve1_works() {
-        assert_eq!(Some(5), solve1("bvwbjplbgvbhsrlpgdmjqwftvncz"));
-        assert_eq!(Some(6), solve1("nppdvjthqldpwncqszvftbrmjlhg"));
-        assert_eq!(Some(10), solve1("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg"));
-        assert_eq!(Some(11), solve1("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw"));
+        assert_eq!(
+            Some(5),
+            solve1(&b"bvwbjplbgvbhsrlpgdmjqwftvncz"[..]).unwrap()
+        );
+        assert_eq!(
+            Some(6),
+            solve1(&b"nppdvjthqldpwncqszvftbrmjlhg"[..]).unwrap()
+        );
+        assert_eq!(
+            Some(10),
+            solve1(&b"nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg"[..]).unwrap()
+        );
+        assert_eq!(
+            Some(11),
+            solve1(&b"zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw"[..]).unwrap()
+        );
     }
 
     #[test]
     fn solve2_works() {
-        assert_eq!(Some(19), solve2("mjqjpqmgbljsphdztnvjfqwrcgsmlb"));
-        assert_eq!(Some(23), solve2("bvwbjplbgvbhsrlpgdmjqwftvncz"));
-        assert_eq!(Some(23), solve2("nppdvjthqldpwncqszvftbrmjlhg"));
-        assert_eq!(Some(29), solve2("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg"));
-        assert_eq!(Some(26), solve2("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw"));
+        assert_eq!(
+            Some(19),
+            solve2(&b"mjqjpqmgbljsphdztnvjfqwrcgsmlb"[..]).unwrap()
+        );
+        assert_eq!(
+            Some(23),
+            solve2(&b"bvwbjplbgvbhsrlpgdmjqwftvncz"[..]).unwrap()
+        );
+        assert_eq!(
+            Some(23),
+            solve2(&b"nppdvjthqldpwncqszvftbrmjlhg"[..]).unwrap()
+        );
+        assert_eq!(
+            Some(29),
+            solve2(&b"nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg"[..]).unwrap()
+        );
+        assert_eq!(
+            Some(26),
+            solve2(&b"zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw"[..]).unwrap()
+        );
+    }
+
+    #[test]
+    fn handles_short_and_repetitive_streams() {
+        assert_eq!(None, solve1(&b"abc"[..]).unwrap());
+        assert_eq!(None, solve1(&b"abababababababab"[..]).unwrap());
+        assert_eq!(Some(1), solve(&b"a"[..], 1).unwrap());
+        assert_eq!(Some(0), solve(&b""[..], 0).unwrap());
+        assert_eq!(
+            Some(27),
+            solve(&b"aabcdefghijklmnopqrstuvwxyz"[..], 26).unwrap()
+        );
+    }
+
+    #[test]
+    fn finds_markers_across_buffer_boundaries() {
+        let mut stream = vec![b'a'; 20_000];
+        stream.extend(b"bcd");
+        assert_eq!(Some(20_003), solve1(&stream[..]).unwrap());
     }
 }
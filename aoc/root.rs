@@ -1,8 +1,9 @@
-use aoc2022::Part;
+use aoc2022::{Options, Part};
 use clap::{Parser, ValueEnum};
 use std::{
     fs::File,
     io::{self, stdin, Read},
+    process,
 };
 
 // Add all of the problem modules here.
@@ -46,6 +47,9 @@ struct Cli {
         default_value = "-"
     )]
     file: String,
+
+    #[command(flatten)]
+    options: Options,
 }
 
 #[derive(Debug, Clone, ValueEnum)]
@@ -90,11 +94,11 @@ fn main() {
     let args = Cli::parse();
     let reader = get_reader(&args.file).unwrap();
     let result = match args.problem {
-        Problem::Day1 => day1::execute(&args.part, reader),
-        Problem::Day2 => day2::execute(&args.part, reader),
-        Problem::Day3 => day3::execute(&args.part, reader),
-        Problem::Day4 => day4::execute(&args.part, reader),
-        Problem::Day5 => day5::execute(&args.part, reader),
+        Problem::Day1 => day1::execute(&args.part, &args.options, reader),
+        Problem::Day2 => day2::execute(&args.part, &args.options, reader),
+        Problem::Day3 => day3::execute(&args.part, &args.options, reader),
+        Problem::Day4 => day4::execute(&args.part, &args.options, reader),
+        Problem::Day5 => day5::execute(&args.part, &args.options, reader),
         Problem::Day6 => day6::execute(&args.part, reader),
         Problem::Day7 => day7::execute(&args.part, reader),
         Problem::Day8 => day8::execute(&args.part, reader),
@@ -112,10 +116,13 @@ fn main() {
         Problem::Day20 => day20::execute(&args.part, reader),
         Problem::Day21 => day21::execute(&args.part, reader),
         Problem::Day22 => day22::execute(&args.part, reader),
-        Problem::Day23 => day23::execute(&args.part, reader),
+        Problem::Day23 => day23::execute(&args.part, &args.options, reader),
         Problem::Day24 => day24::execute(&args.part, reader),
         Problem::Day25 => day25::execute(&args.part, reader),
     };
 
-    result.unwrap();
+    if let Err(err) = result {
+        eprintln!("{err}");
+        process::exit(1);
+    }
 }
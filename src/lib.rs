@@ -1,8 +1,8 @@
 #[cfg(test)]
 mod tests {}
-use std::{num::ParseIntError, string};
+use std::{fmt, num::ParseIntError, string};
 
-use clap::ValueEnum;
+use clap::{Args, ValueEnum};
 
 #[derive(Debug, Clone, ValueEnum)]
 pub enum Part {
@@ -10,6 +10,113 @@ pub enum Part {
     Part2,
 }
 
+/// Alternative things a problem can do with its input, besides solving one of the parts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Mode {
+    /// Summary statistics about the input.
+    Stats,
+    /// Try every way of reading an ambiguous input, and report which is best.
+    Infer,
+    /// Score every player in a game, not just ourselves.
+    Tournament,
+    /// Show what each piece of the input contributed to the answer.
+    Explain,
+    /// Work out how the input should be grouped, rather than assuming related entries are next to each other.
+    Regroup,
+    /// Draw the input as a diagram, like the ones in the puzzle text.
+    Render,
+    /// Report how much of a shared space the input covers, and where it's left bare or crowded.
+    Coverage,
+}
+
+/// The rules to play by, for problems that are games.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Game {
+    #[default]
+    RockPaperScissors,
+    RockPaperScissorsLizardSpock,
+}
+
+/// How reports should be written out.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    #[default]
+    Table,
+    Json,
+}
+
+/// Extra knobs that only some problems make use of. Problems that don't understand an option ignore it.
+#[derive(Debug, Default, Clone, Args)]
+pub struct Options {
+    #[arg(
+        long,
+        help = "Print every intermediate frame of a simulation to stderr."
+    )]
+    pub frames: bool,
+
+    #[arg(
+        long,
+        value_name = "N",
+        help = "How many of the top entries to consider, where the problem ranks entries."
+    )]
+    pub top: Option<usize>,
+
+    #[arg(
+        long,
+        help = "Run the problem in a different mode instead of solving the given part."
+    )]
+    pub mode: Option<Mode>,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = Format::Table,
+        help = "The format to write reports in."
+    )]
+    pub format: Format,
+
+    #[arg(
+        long,
+        help = "Reject input that isn't perfectly formed instead of tidying it up."
+    )]
+    pub strict: bool,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = Game::RockPaperScissors,
+        help = "The rules to play by."
+    )]
+    pub game: Game,
+
+    #[arg(
+        long,
+        help = "Include a row for every round or step in reports that support it."
+    )]
+    pub per_round: bool,
+
+    #[arg(
+        long,
+        value_name = "N",
+        help = "How many entries make up a group, where the problem groups its input."
+    )]
+    pub group_size: Option<usize>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        help = "The most a machine can carry at once, where the problem has such a limit."
+    )]
+    pub capacity: Option<usize>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Stop a simulation after its first N steps, where the problem supports it."
+    )]
+    pub steps: Option<usize>,
+}
+
 pub type Result<T> = std::result::Result<T, Error>;
 
 #[derive(Debug, Eq, PartialEq)]
@@ -17,8 +124,33 @@ pub enum Error {
     ParseIntError,
     IOError,
     Utf8Error,
+    InvalidInput(String),
+    UnsupportedMode(Mode),
+    /// The input was malformed at the given line, counting from 1.
+    Parse {
+        line: usize,
+        message: String,
+    },
 }
 
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::ParseIntError => write!(f, "invalid number"),
+            Error::IOError => write!(f, "could not read input"),
+            Error::Utf8Error => write!(f, "input is not valid UTF-8"),
+            Error::InvalidInput(message) => write!(f, "{message}"),
+            Error::UnsupportedMode(mode) => match mode.to_possible_value() {
+                Some(value) => write!(f, "this problem has no {} mode", value.get_name()),
+                None => write!(f, "this problem does not support {mode:?}"),
+            },
+            Error::Parse { line, message } => write!(f, "line {line}: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
 impl From<ParseIntError> for Error {
     fn from(_: ParseIntError) -> Self {
         Self::ParseIntError